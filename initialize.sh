@@ -2,6 +2,8 @@ touch ./db/packageStatus.json
 touch ./db/packageStatus.bak.json
 touch ./db/packageMarks.json
 touch ./db/packageMarks.bak.json
+touch ./db/packageHistory.json
+touch ./db/packageHistory.bak.json
 touch ./config/alias.json
 
 chmod +x start-plct-tg-bot.sh
@@ -1,5 +1,8 @@
 cd "$(dirname "$0")"
 
-cat log.log | grep PID | tail -n 1 | cut -d " " -f 3 | xargs kill
+pid=$(cat log.log | grep PID | tail -n 1 | cut -d " " -f 3)
+kill "$pid"
+# the old process drains queued messages for up to PLCT_SHUTDOWN_GRACE_PERIOD before releasing plct.lock
+while kill -0 "$pid" 2>/dev/null; do sleep 1; done
 
 ./start-plct-tg-bot.sh
@@ -0,0 +1,85 @@
+//@ts-check
+
+/**
+ * changes of assignments on a packageStatus array. Kept out of utils.js, which loads db/ once required,
+ * so that they can be tested on their own
+ */
+
+/**
+ * @typedef {import("./utils").PackageInterface} PackageInterface
+ * @typedef {{ userid: number; username: string | undefined; packages: PackageInterface[]; }} PackagerInterface
+ */
+
+/**
+ * @param {PackagerInterface[]} status
+ * @param {string} pkgname
+ * @param {number} userId
+ * @param {string | undefined} username stored if `userId` holds nothing yet
+ * @param {number} now becomes `lastActive` and `assignedAt` of the package
+ * @returns {number | null} who held `pkgname` already: `userId` for a repeated claim, someone else for a conflict.
+ * The status is only changed if it's null, i.e. the package was free and is now assigned to `userId`
+ */
+function assignPackage(status, pkgname, userId, username, now) {
+  const owner = status.find(user => user.packages.some(pkg => pkg.name === pkgname));
+  if(owner) {
+    return owner.userid;
+  }
+  const pkg = { name: pkgname, lastActive: now, assignedAt: now };
+  const user = status.find(user => user.userid === userId);
+  if(user) {
+    user.packages.push(pkg);
+  } else {
+    status.push({ userid: userId, username, packages: [pkg] });
+  }
+  return null;
+}
+
+/**
+ * @param {PackagerInterface[]} status
+ * @param {string} pkgname assigned to someone else than `newUserId`
+ * @param {number} newUserId
+ * @param {number} now becomes `lastActive` and `assignedAt` of the package
+ * @returns {() => void} puts the package back where it was, e.g. when storing the move fails.
+ * Marks are stored by pkgname, so they aren't touched either way
+ */
+function movePackage(status, pkgname, newUserId, now) {
+  const oldUser = status.find(user => user.packages.some(pkg => pkg.name === pkgname));
+  const index = oldUser.packages.findIndex(pkg => pkg.name === pkgname);
+  const [targetPackage] = oldUser.packages.splice(index, 1);
+  const { lastActive, assignedAt } = targetPackage;
+  targetPackage.lastActive = now;
+  targetPackage.assignedAt = now;
+  let newUser = status.find(user => user.userid === newUserId);
+  const addedUser = !newUser;
+  if(addedUser) {
+    newUser = { userid: newUserId, username: undefined, packages: [] };
+    status.push(newUser);
+  }
+  newUser.packages.push(targetPackage);
+
+  return () => {
+    newUser.packages.splice(newUser.packages.indexOf(targetPackage), 1);
+    if(addedUser) {
+      status.splice(status.indexOf(newUser), 1);
+    }
+    Object.assign(targetPackage, { lastActive, assignedAt });
+    oldUser.packages.splice(index, 0, targetPackage);
+  };
+}
+
+/**
+ * @param {number | null} ownerId from assignPackage()
+ * @param {number} requesterId who tried to claim the package
+ * @param {boolean} enabled PLCT_NOTIFY_ADD_CONFLICT
+ * @returns {boolean} whether to ping the owner that the requester would like the package too:
+ * only for a conflict, never for a repeated claim of one's own package
+ */
+function shouldPingOwner(ownerId, requesterId, enabled) {
+  return enabled && ownerId !== null && ownerId !== requesterId;
+}
+
+module.exports = {
+  assignPackage,
+  movePackage,
+  shouldPingOwner,
+};
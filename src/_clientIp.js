@@ -0,0 +1,27 @@
+//@ts-check
+
+/**
+ * @param {string | undefined} remoteAddress of the socket
+ * @param {string | string[] | undefined} forwardedFor the X-Forwarded-For header
+ * @param {boolean} trustProxy whether the bot is behind a reverse proxy (PLCT_TRUST_PROXY); X-Forwarded-For is ignored otherwise,
+ * since any client can send it
+ * @param {string[]} trustedProxies addresses of the reverse proxies, whose X-Forwarded-For can be believed
+ * @returns {string} the nearest hop that is not a trusted proxy; hops further left are given by the client and may be forged
+ */
+function getClientIp(remoteAddress, forwardedFor, trustProxy, trustedProxies) {
+  /**
+   * @param {string} addr
+   */
+  const normalize = (addr) => addr.trim().replace(/^::ffff:(\d+\.\d+\.\d+\.\d+)$/, "$1");
+  let ip = normalize(remoteAddress || "");
+  if(!trustProxy || forwardedFor === undefined) return ip;
+  const hops = (Array.isArray(forwardedFor) ? forwardedFor.join(",") : forwardedFor).split(",").map(normalize).filter(Boolean);
+  while(trustedProxies.includes(ip) && hops.length > 0) {
+    ip = hops.pop();
+  }
+  return ip;
+}
+
+module.exports = {
+  getClientIp,
+};
@@ -0,0 +1,92 @@
+//@ts-check
+
+const { readFileSync } = require("fs");
+
+const DEFAULT_CONFIG_FILE = "./config/.env";
+const EXAMPLE_CONFIG_FILE = __dirname + "/../config/.env.example";
+
+// without them the bot can't start or has nowhere to send messages
+const REQUIRED_KEYS = ["PLCT_BOT_TOKEN", "PLCT_CHAT_ID"];
+
+const NUMERIC_KEYS = [
+  "PLCT_BOT_ADMIN_USERID",
+  "PLCT_CHAT_ID",
+  "PLCT_HTTP_SIGNATURE_MAX_AGE",
+  "PLCT_RATE_LIMIT_BURST",
+  "PLCT_RATE_LIMIT_PER_SECOND",
+  "PLCT_DELETE_RATE_LIMIT",
+  "PLCT_DELETE_RATE_LIMIT_WINDOW",
+  "PLCT_SEND_MESSAGE_MAX_RETRIES",
+  "PLCT_SEND_MESSAGE_RETRY_DELAY",
+  "PLCT_SEND_MESSAGE_MAX_RETRY_AFTER",
+  "PLCT_SEND_MESSAGE_INTERVAL",
+  "PLCT_METRICS_PORT",
+  "PLCT_HEALTH_CHECK_TIMEOUT",
+  "PLCT_SHUTDOWN_GRACE_PERIOD",
+  "PLCT_IDEMPOTENCY_KEY_TTL",
+  "PLCT_PENDING_NOTIFICATION_INTERVAL",
+  "PLCT_STALE_REMINDER_DAYS",
+  "PLCT_STALE_REMINDER_INTERVAL",
+  "PLCT_AUTO_DROP_DAYS",
+  "PLCT_AUTO_DROP_INTERVAL",
+  "PLCT_WEEKLY_REPORT_WEEKDAY",
+  "PLCT_WEEKLY_REPORT_HOUR",
+];
+
+const QUIET_HOURS_KEYS = ["PLCT_STALE_REMINDER_QUIET_HOURS", "PLCT_NOTIFICATION_QUIET_HOURS"];
+
+// the bundles of _messages.js
+const LANGUAGES = ["zh", "en"];
+
+/**
+ * @description `--config=path` or PLCT_CONFIG, ./config/.env by default
+ */
+function getConfigPath() {
+  const arg = process.argv.find(arg => arg.startsWith("--config="));
+  if(arg) {
+    return arg.slice("--config=".length);
+  }
+  return process.env["PLCT_CONFIG"] || DEFAULT_CONFIG_FILE;
+}
+
+function getDefaultConfig() {
+  return readFileSync(EXAMPLE_CONFIG_FILE, "utf8");
+}
+
+/**
+ * @param {NodeJS.ProcessEnv} env
+ * @returns {string[]} one message for every offending key, empty if the config is fine
+ */
+function validateConfig(env) {
+  const errors = [];
+  for(const key of REQUIRED_KEYS) {
+    if(!env[key]) {
+      errors.push(`${key} is required`);
+    }
+  }
+  for(const key of NUMERIC_KEYS) {
+    if(env[key] && !Number.isFinite(Number(env[key]))) {
+      errors.push(`${key} should be a number, got "${env[key]}"`);
+    }
+  }
+  for(const key of QUIET_HOURS_KEYS) {
+    if(env[key] && !/^([01]?\d|2[0-3])-([01]?\d|2[0-3])$/.test(env[key])) {
+      errors.push(`${key} should look like "22-8", got "${env[key]}"`);
+    }
+  }
+  const destinations = env["PLCT_CHAT_DESTINATIONS"];
+  if(destinations && !destinations.split(",").every(pair => /^\s*(merge|marks|admin)=-?\d+(:\d+)?\s*$/.test(pair))) {
+    errors.push(`PLCT_CHAT_DESTINATIONS should look like "merge=-100123:45,admin=-100456" (purposes: merge, marks, admin), got "${destinations}"`);
+  }
+  const lang = env["PLCT_LANG"];
+  if(lang && !LANGUAGES.includes(lang)) {
+    errors.push(`PLCT_LANG should be one of ${LANGUAGES.join(", ")}, got "${lang}"`);
+  }
+  return errors;
+}
+
+module.exports = {
+  getConfigPath,
+  getDefaultConfig,
+  validateConfig,
+};
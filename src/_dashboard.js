@@ -0,0 +1,128 @@
+//@ts-check
+
+/**
+ * server-side rendered status page, plain HTML + inline CSS, no client-side JavaScript
+ */
+
+/**
+ * @typedef DashboardMarkInterface
+ * @prop {string} name
+ * @prop {string} desc
+ * @prop {string} comment
+ * @prop {string} by alias of whoever made the mark
+ */
+
+/**
+ * @typedef DashboardPackageInterface
+ * @prop {string} name
+ * @prop {DashboardMarkInterface[]} marks
+ */
+
+/**
+ * @typedef DashboardPackagerInterface
+ * @prop {string} alias
+ * @prop {number} tgUid
+ * @prop {DashboardPackageInterface[]} packages
+ */
+
+// badge colors by the kind of mark, anything not listed here is grey
+const MARK_COLORS = {
+  failing: "#d73a49",
+  flaky: "#d73a49",
+  stuck: "#e36209",
+  unknown: "#e36209",
+  upstreamed: "#0366d6",
+  outdated: "#0366d6",
+  outdated_dep: "#0366d6",
+  missing_dep: "#0366d6",
+  ready: "#28a745",
+};
+
+/**
+ * @param {any} unsafe
+ */
+function escapeHtml(unsafe) {
+  return String(unsafe)
+    .replace(/&/g, "&amp;")
+    .replace(/</g, "&lt;")
+    .replace(/>/g, "&gt;")
+    .replace(/"/g, "&quot;")
+    .replace(/'/g, "&#39;");
+}
+
+/**
+ * @param {DashboardMarkInterface} mark
+ */
+function renderMark(mark) {
+  const color = MARK_COLORS[mark.name] || "#6a737d";
+  const title = `${mark.desc} (by ${mark.by})`;
+  const badge = `<span class="mark" style="background:${color}" title="${escapeHtml(title)}">${escapeHtml(mark.name)}</span>`;
+  // the reason (e.g. why it is stuck) otherwise only lives in the chat history
+  return mark.comment ? `${badge} <span class="comment">${escapeHtml(mark.comment)}</span>` : badge;
+}
+
+/**
+ * @param {DashboardPackageInterface[]} packages
+ */
+function renderPackageRows(packages) {
+  if(packages.length === 0) {
+    return `<tr><td colspan="2" class="empty">(none)</td></tr>`;
+  }
+  return packages.map(pkg => `<tr><td>${escapeHtml(pkg.name)}</td><td>${pkg.marks.map(renderMark).join(" ")}</td></tr>`).join("\n");
+}
+
+/**
+ * @param {{
+    packagers: DashboardPackagerInterface[];
+    unassigned: DashboardPackageInterface[] | null;
+    packager: string | null;
+  }} data `unassigned` is null when filtered by `packager`
+ */
+function renderDashboard(data) {
+  const sections = data.packagers.map(packager => `
+<h2>${escapeHtml(packager.alias)} <small>(${packager.packages.length})</small></h2>
+<table>
+${renderPackageRows(packager.packages)}
+</table>`);
+  if(data.unassigned !== null) {
+    sections.push(`
+<h2>marked but unassigned <small>(${data.unassigned.length})</small></h2>
+<table>
+${renderPackageRows(data.unassigned)}
+</table>`);
+  }
+  const total = data.packagers.reduce((sum, packager) => sum + packager.packages.length, 0);
+  return `<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>plct archrv packages</title>
+<style>
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; min-width: 30em; }
+td { border-bottom: 1px solid #e1e4e8; padding: 0.2em 0.6em; }
+td:first-child { font-family: monospace; }
+.mark { color: #fff; border-radius: 0.8em; padding: 0.1em 0.5em; font-size: 0.85em; }
+.empty, .comment { color: #6a737d; }
+.comment { font-size: 0.85em; }
+</style>
+</head>
+<body>
+<h1>plct archrv packages</h1>
+<form method="get">
+<input name="packager" placeholder="tg uid or alias" value="${escapeHtml(data.packager || "")}">
+<button type="submit">filter</button>
+${data.packager !== null ? `<a href="?">all</a>` : ""}
+</form>
+<p>${total} assigned packages</p>
+${sections.join("\n")}
+</body>
+</html>
+`;
+}
+
+module.exports = {
+  escapeHtml,
+  renderDashboard,
+};
@@ -0,0 +1,34 @@
+//@ts-check
+
+/**
+ * lookups in the package history. Kept out of utils.js, which loads db/ once required,
+ * so that they can be tested on their own
+ */
+
+/**
+ * @typedef {import("./utils").HistoryInterface} HistoryInterface
+ */
+
+/**
+ * @param {HistoryInterface[]} history in time order, oldest first
+ * @param {string | null} pkgname null for all packages
+ * @param {number} limit
+ * @param {number} [since] only entries at or after this time (ms)
+ * @param {number} [until] only entries at or before this time (ms)
+ * @returns {HistoryInterface[]} newest first
+ */
+function filterHistory(history, pkgname, limit, since = 0, until = Infinity) {
+  const ret = [];
+  // entries are appended in time order, so everything before `since` can be skipped at once
+  for(let i = history.length - 1; i >= 0 && ret.length < limit && history[i].time >= since; i--) {
+    if(history[i].time > until) continue;
+    if(pkgname === null || history[i].pkgname === pkgname) {
+      ret.push(history[i]);
+    }
+  }
+  return ret;
+}
+
+module.exports = {
+  filterHistory,
+};
@@ -0,0 +1,43 @@
+//@ts-check
+
+const http = require("http");
+
+/**
+ * thrown by route handlers, the dispatcher turns it into a text/plain response
+ */
+class HttpError extends Error {
+  /**
+   * @param {number} status
+   * @param {string} [detail] appended to the status text, e.g. `Bad Request: unknown mark foo`
+   */
+  constructor(status, detail) {
+    const statusText = http.STATUS_CODES[status] || "Error";
+    super(detail ? `${statusText}: ${detail}` : statusText);
+    this.name = "HttpError";
+    this.status = status;
+  }
+}
+
+/**
+ * @param {http.ServerResponse} res
+ * @param {any} err
+ * @description anything other than an HttpError is answered with 500.
+ * Bodies of 5xx mention the X-Request-Id of the response, so that a reported failure can be found in the log
+ */
+function respondError(res, err) {
+  if(res.headersSent) {
+    // e.g. a streamed response failed halfway, nothing better to do
+    res.end();
+    return;
+  }
+  const status = err instanceof HttpError ? err.status : 500;
+  const message = err instanceof HttpError ? err.message : `Internal Server Error: ${err}`;
+  const requestId = res.getHeader("X-Request-Id");
+  res.writeHead(status, { 'Content-Type': 'text/plain' });
+  res.end(status >= 500 && requestId !== undefined ? `${message} (request ${requestId})` : message);
+}
+
+module.exports = {
+  HttpError,
+  respondError,
+};
@@ -0,0 +1,70 @@
+//@ts-check
+
+/**
+ * the responses stored for Idempotency-Key. Kept out of plct-archrv-bot.js so that they can be tested on their own
+ */
+
+/**
+ * @template R
+ */
+class IdempotencyCache {
+  /**
+   * @param {number} ttl in ms, how long a response is replayed
+   * @param {number} maxKeys the oldest keys are forgotten beyond this
+   * @param {() => number} [now] the clock, replaced in tests
+   */
+  constructor(ttl, maxKeys, now = Date.now) {
+    this.ttl = ttl;
+    this.maxKeys = maxKeys;
+    this.now = now;
+    /**
+     * @type {Map<string, { expiresAt: number; done: Promise<R | null>; }>}
+     * the response by key, null when it is not to be replayed
+     */
+    this.responses = new Map();
+  }
+
+  /**
+   * @param {string} key
+   * @param {() => Promise<R | null>} run handles the request, resolving with the response to replay for
+   * the same key later, or null (e.g. for a failure) so that the request can be retried with the key
+   * @returns {Promise<{ replayed: boolean; response: R | null; }>} the stored response without running again
+   * if the key has been seen, what `run` resolved with otherwise
+   */
+  async run(key, run) {
+    const now = this.now();
+    // the map is in insertion order, so the oldest keys come first
+    for(const [storedKey, entry] of this.responses) {
+      if(entry.expiresAt > now && this.responses.size < this.maxKeys) break;
+      this.responses.delete(storedKey);
+    }
+    // a duplicate arriving while the first one is still running waits for it
+    for(let stored = this.responses.get(key); stored; stored = this.responses.get(key)) {
+      const response = await stored.done;
+      if(response) {
+        return { replayed: true, response };
+      }
+    }
+
+    /**
+     * @type {(response: R | null) => void}
+     */
+    let resolveDone = () => {};
+    const done = new Promise(resolve => resolveDone = resolve);
+    this.responses.set(key, { expiresAt: now + this.ttl, done });
+    let response = null;
+    try {
+      response = await run();
+      return { replayed: false, response };
+    } finally {
+      if(response === null) {
+        this.responses.delete(key);
+      }
+      resolveDone(response);
+    }
+  }
+}
+
+module.exports = {
+  IdempotencyCache,
+};
@@ -0,0 +1,36 @@
+//@ts-check
+
+/**
+ * serializing of work on the same key, e.g. deletions of a package. Kept out of plct-archrv-bot.js
+ * so that it can be tested on its own
+ */
+
+class KeyedLock {
+  constructor() {
+    /**
+     * @type {Map<string, Promise<void>>} key -> settles when the last queued work on it is done
+     */
+    this.tails = new Map();
+  }
+
+  /**
+   * @template T
+   * @param {string} key
+   * @param {() => Promise<T>} fn
+   * @returns {Promise<T>} what `fn` resolves or rejects with. Works on the same key run one after another,
+   * in the order of the calls, whether the earlier ones succeed or not
+   */
+  run(key, fn) {
+    const result = (this.tails.get(key) || Promise.resolve()).then(fn);
+    const done = result.then(() => {}, () => {});
+    this.tails.set(key, done);
+    done.then(() => {
+      if(this.tails.get(key) === done) this.tails.delete(key);
+    });
+    return result;
+  }
+}
+
+module.exports = {
+  KeyedLock,
+};
@@ -0,0 +1,69 @@
+//@ts-check
+
+/**
+ * checks of the marks of POST /mark. Kept out of plct-archrv-bot.js so that they can be tested on their own
+ */
+
+const { HttpError } = require("./_httpError");
+
+/**
+ * @typedef {{ mark: string; comment?: string; }} MarkItemInterface
+ * @typedef {NonNullable<ReturnType<typeof import("./utils").getMarkConfig>>} MarkConfigInterface
+ */
+
+/**
+ * @param {any} body the JSON body, a single `{ mark, comment }` object or an array of them
+ * @returns {MarkItemInterface[]} throws a 400 HttpError if an item is malformed or a mark is repeated
+ */
+function getMarkItems(body) {
+  const items = Array.isArray(body) ? body : [body];
+  if(items.length === 0 || items.some(item => !item || typeof item.mark !== "string" ||
+    (item.comment !== undefined && typeof item.comment !== "string"))) {
+    throw new HttpError(400);
+  }
+  if(new Set(items.map(item => item.mark)).size !== items.length) {
+    throw new HttpError(400, "duplicate marks");
+  }
+  return items;
+}
+
+/**
+ * @param {MarkItemInterface[]} items from getMarkItems()
+ * @param {{ name: string; comment: string; }[]} existingMarks the marks the package has
+ * @param {(mark: string) => MarkConfigInterface} getMarkConfig throws a 400 HttpError for unknown marks
+ * @param {string} timeStr appended to the comments of marks with `appendTimeComment`
+ * @returns {{ mark: string; comment: string; markConfig: MarkConfigInterface; }[]} the marks to set, skipping those
+ * already set with the same comment. Every mark is checked before any is set, so that one bad mark rejects
+ * the whole request: a 403 HttpError for marks not allowed by hand (`allowUserModification.mark`, as with /mark),
+ * a 400 one for missing comments
+ */
+function planMarks(items, existingMarks, getMarkConfig, timeStr) {
+  const pending = [];
+  for(const item of items) {
+    const mark = item.mark;
+    let comment = item.comment ? item.comment.trim() : "";
+    const markConfig = getMarkConfig(mark);
+    if(!markConfig.allowUserModification.mark) {
+      throw new HttpError(403, `mark ${mark} can't be set by hand`);
+    }
+    const existingMark = existingMarks.find(markObj => markObj.name === mark);
+    if(existingMark && (item.comment === undefined || existingMark.comment === comment)) {
+      // e.g. CI marking a still stuck package as stuck again, nothing to notify about
+      continue;
+    }
+    if(comment === "" && markConfig.requireComment) {
+      throw new HttpError(400, `mark ${mark} requires a comment`);
+    }
+    if(markConfig.appendTimeComment) {
+      comment += " " + timeStr;
+      comment = comment.trim();
+    }
+    pending.push({ mark, comment, markConfig });
+  }
+  return pending;
+}
+
+module.exports = {
+  getMarkItems,
+  planMarks,
+};
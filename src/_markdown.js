@@ -0,0 +1,96 @@
+//@ts-check
+
+/**
+ * escaping of Telegram MarkdownV2. Kept out of utils.js, which loads db/ once required,
+ * so that _messages.js and the tests can use it on its own
+ */
+
+/**
+ * @param {string | number} unsafeMd unsafe markdown v2 text
+ */
+function toSafeMd(unsafeMd) {
+  unsafeMd = String(unsafeMd);
+  // see https://core.telegram.org/bots/api#markdownv2-style
+  // eslint-disable-next-line no-useless-escape
+  return unsafeMd.replace(/([[\]()_*~`>#+\-=|{}\.!\\])/g, "\\$1");
+}
+
+/**
+ * @param {TemplateStringsArray} unsafeMdArr unsafe markdown v2 texts
+ * @param {any[]} safeMdArr
+ * @description Use this with template strings. ```_safemd`unsafe ${"safe"} unsafe2}` ```
+ */
+function _safemd(unsafeMdArr, ...safeMdArr) {
+  safeMdArr = safeMdArr.map(safeMd => String(safeMd));
+  // see https://core.telegram.org/bots/api#markdownv2-style
+  // eslint-disable-next-line no-useless-escape
+  const escapedMdArr = unsafeMdArr.map(unsafeMd => unsafeMd.replace(/([[\]()_*~`>#+\-=|{}\.!\\])/g, "\\$1"));
+  let result = "";
+  for(let i = 0; i < safeMdArr.length; i++) {
+    result += escapedMdArr[i];
+    result += safeMdArr[i];
+  }
+  result += escapedMdArr[escapedMdArr.length - 1];
+  return result;
+}
+
+/**
+ * @param {string} unsafeCode unsafe markdown v2 code
+ * @returns {string} safe code without backquotes
+ */
+function toSafeCode(unsafeCode) {
+  return unsafeCode.replace(/([`\\])/g, "\\$1");
+}
+
+/**
+ * @param {string} unsafeCode unsafe markdown v2 code
+ * @returns {string} safe code with backquotes
+ */
+function wrapCode(unsafeCode) {
+  return `\`${toSafeCode(unsafeCode)}\``;
+}
+
+/**
+ * @param {string | number} uid
+ * @param {string} [username]
+ * @param {string} [firstName]
+ * @param {string} [lastName]
+ * @param {boolean} [tag]
+ */
+function getMentionLink(uid, username, firstName = "", lastName = "", tag = false) {
+  let userDisplayName = "@" + username;
+  if(!username) {
+    // see https://core.telegram.org/bots/api#markdownv2-style
+    userDisplayName = toSafeMd(`${firstName} ${lastName}`);
+    userDisplayName = userDisplayName.trimEnd();
+  } else {
+    userDisplayName = toSafeMd(userDisplayName);
+  }
+  if(tag) {
+    return `[${userDisplayName}\u200B](tg://user?id=${uid})`;
+  }
+  return `[${userDisplayName}](tg://user?id=${uid})`;
+}
+
+/**
+ * @param {string} baseLogDir PLCT_BASE_LOG_DIR, the log of `{pkgname}` in it, or blank for no link
+ * @param {string} pkgname
+ * @param {string} unsafeMdText
+ */
+function getLogDirLinkMd(baseLogDir, pkgname, unsafeMdText) {
+  const safeMdText = toSafeMd(unsafeMdText);
+  if(!baseLogDir) return safeMdText;
+  // see https://core.telegram.org/bots/api#markdownv2-style
+  // inside the (...) part of inline links, only ")" and "\" must be escaped
+  const safeUrl = baseLogDir.replace("{pkgname}", encodeURIComponent(pkgname)).replace(/([)\\])/g, "\\$1");
+  return `[${safeMdText}](${safeUrl})`;
+}
+
+module.exports = {
+  toSafeMd,
+  _safemd,
+  toSafeCode,
+  wrapCode,
+  getMentionLink,
+  getLogDirLinkMd,
+};
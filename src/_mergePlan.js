@@ -0,0 +1,114 @@
+//@ts-check
+
+/**
+ * what a /delete would do, for its dry_run, and how it answers. Kept out of plct-archrv-bot.js
+ * so that they can be tested on their own
+ */
+
+const { getMentionLink } = require("./_markdown");
+
+/**
+ * @typedef {import("./_assign").PackagerInterface} PackagerInterface
+ * @typedef {typeof import("./utils").packageMarks} PackageMarksInterface
+ * @typedef {import("./utils").DeleteStatus} DeleteStatus
+ *
+ * @typedef MergeStateInterface
+ * @prop {PackagerInterface[]} packageStatus
+ * @prop {PackageMarksInterface} packageMarks
+ * @prop {string[]} clearOnMergeMarks marks cleared once the package is merged
+ * @prop {string[]} refMarks marks of other packages which point to the merged package in their comments
+ * @prop {(uid: number) => string} getAlias
+ * @prop {ReturnType<typeof import("./_messages").getMessages>} messages
+ */
+
+/**
+ * @param {string} pkgname
+ * @param {DeleteStatus} status
+ * @param {MergeStateInterface} state only read, nothing in it is changed
+ * @description what _autoMerge() would do, without changing anything or sending any message.
+ * `messages` are the MarkdownV2 texts in the order they would be sent, before throttling merges them
+ */
+function planAutoMerge(pkgname, status, state) {
+  const { packageStatus, packageMarks, clearOnMergeMarks, refMarks, getAlias, messages: tgMessages } = state;
+  const owner = packageStatus.find(user => user.packages.some(pkg => pkg.name === pkgname));
+  const userId = owner ? owner.userid : null;
+  /**
+   * @type {string[]}
+   */
+  const messages = [];
+  /**
+   * @type {{ pkgname: string; mark: string; }[]}
+   */
+  const unmarks = [];
+  /**
+   * @type {{ pkgname: string; mark: string; comment: string; }[]}
+   */
+  const updates = [];
+  if(userId !== null) {
+    const link = getMentionLink(userId, null, getAlias(userId));
+    messages.push(tgMessages.mergedPing({ link, pkgname, status }));
+  }
+  const pkgMarks = packageMarks.find(pkg => pkg.name === pkgname);
+  const currMarks = pkgMarks ? pkgMarks.marks.map(mark => mark.name) : [];
+  for(const mark of clearOnMergeMarks.filter(mark => currMarks.includes(mark))) {
+    unmarks.push({ pkgname, mark });
+    messages.push(tgMessages.mergedUnmark({ pkgname, status, mark }));
+  }
+  const ref = `[${pkgname}]`.toLowerCase();
+  const refPackages = packageMarks.filter(pkg => pkg.marks.some(mark => refMarks.includes(mark.name) && mark.comment.toLowerCase().includes(ref)));
+  for(const pkg of refPackages) {
+    /**
+     * @type {Set<string>}
+     */
+    const mentionLinkSet = new Set();
+    const deferredMessages = [];
+    for(const mark of pkg.marks) {
+      if(!refMarks.includes(mark.name)) continue;
+      if(mark.by) {
+        mentionLinkSet.add(getMentionLink(mark.by.uid, null, getAlias(mark.by.uid)));
+      }
+      if(mark.comment.toLowerCase() === ref) {
+        unmarks.push({ pkgname: pkg.name, mark: mark.name });
+        deferredMessages.push(tgMessages.mergedRefUnmark({ pkgname: pkg.name, refPkgname: pkgname, status, mark: mark.name }));
+      } else {
+        // the first reference, in any case, like _autoMerge()
+        const index = mark.comment.toLowerCase().indexOf(ref);
+        const comment = (index < 0 ? mark.comment : mark.comment.slice(0, index) + mark.comment.slice(index + ref.length)).trim();
+        updates.push({ pkgname: pkg.name, mark: mark.name, comment });
+        deferredMessages.push(tgMessages.mergedRefRemoved({ pkgname: pkg.name, refPkgname: pkgname, mark: mark.name }));
+      }
+    }
+    if(mentionLinkSet.size > 0) {
+      messages.push(tgMessages.cc({ links: Array.from(mentionLinkSet) }));
+    }
+    messages.push(...deferredMessages);
+  }
+  return {
+    packager: userId === null ? null : { tgUid: userId, alias: getAlias(userId) },
+    unmarks,
+    updates,
+    messages,
+  };
+}
+
+/**
+ * @param {{ userId: number | null; } | null} merged what _autoMerge() did, null if there was nothing left to do
+ * @param {boolean} strict `?strict=true`, for callers that want to tell packages nobody held apart
+ * @returns {string | null} the body of the 200, or null for a 404 "package not assigned". Without `strict`
+ * such packages are answered with 200 as well, since CI relies on it and the marks are cleared all the same
+ */
+function getDeleteReply(merged, strict) {
+  if(strict && (merged === null || merged.userId === null)) {
+    return null;
+  }
+  if(merged === null) {
+    // a retried or concurrent call, nobody was pinged again
+    return "already deleted";
+  }
+  return (merged.userId === null ? "package not found;" : "") + "success";
+}
+
+module.exports = {
+  planAutoMerge,
+  getDeleteReply,
+};
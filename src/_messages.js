@@ -0,0 +1,205 @@
+//@ts-check
+
+/**
+ * texts of the notifications sent by the APIs and background jobs, in every language of PLCT_LANG.
+ * Every template returns MarkdownV2; `link`s are mention links from getMentionLink(), other arguments are plain text
+ */
+
+const { _safemd, toSafeMd, wrapCode } = require("./_markdown");
+
+/**
+ * @typedef {{ mark: string; comment: string; }} MarkedInterface
+ * @typedef {import("./utils").DeleteStatus} DeleteStatus
+ */
+
+/**
+ * @typedef WeeklyReportArgs
+ * @prop {string} from first day of the report, YYYY-MM-DD
+ * @prop {string} to last day of the report
+ * @prop {{ alias: string | null; count: number; }[]} closed `alias` is null if the history doesn't tell who held the packages
+ * @prop {string[]} newlyFailing
+ * @prop {{ pkgname: string; alias: string; days: number; }[]} oldest
+ */
+
+const zh = {
+  /**
+   * @type {Record<DeleteStatus, string>} how the pings put each status
+   */
+  deleteStatuses: {
+    ftbfs: "已出包",
+    leaf: "已移出 leaf 列表",
+    rotted: "已移出 rotted 列表",
+  },
+  /**
+   * @param {{ link: string; pkgname: string; status: DeleteStatus; }} args
+   */
+  mergedPing: ({ link, pkgname, status }) =>
+    wrapCode("(auto-merge)") + " ping " + link + toSafeMd(`: ${pkgname} ${zh.deleteStatuses[status]}`),
+  /**
+   * @param {{ link: string; groups: { pkgnames: string[]; status: DeleteStatus; }[]; }} args
+   */
+  mergedPingBatch: ({ link, groups }) =>
+    wrapCode("(auto-merge)") + " ping " + link +
+    toSafeMd(`: ${groups.map(({ pkgnames, status }) => `${pkgnames.join(" ")} ${zh.deleteStatuses[status]}`).join("；")}`),
+  /**
+   * @param {{ reason: string; requestId: string | null; }} args
+   */
+  mergeFailed: ({ reason, requestId }) =>
+    wrapCode("(auto-merge)") + toSafeMd(` failed: ${reason}${requestId ? ` (request ${requestId})` : ""}`),
+  /**
+   * @param {{ pkgname: string; status: DeleteStatus; mark: string; }} args
+   */
+  mergedUnmark: ({ pkgname, status, mark }) =>
+    wrapCode("(auto-unmark)") + toSafeMd(` ${pkgname} ${zh.deleteStatuses[status]}，不再被标记为 ${mark}`),
+  /**
+   * @param {{ pkgname: string; refPkgname: string; status: DeleteStatus; mark: string; }} args
+   */
+  mergedRefUnmark: ({ pkgname, refPkgname, status, mark }) =>
+    wrapCode("(auto-unmark)") + toSafeMd(` ${pkgname} 因 ${refPkgname} ${zh.deleteStatuses[status]}，不再被标记为 ${mark}`),
+  /**
+   * @param {{ pkgname: string; refPkgname: string; mark: string; }} args
+   */
+  mergedRefRemoved: ({ pkgname, refPkgname, mark }) =>
+    wrapCode("(auto-mark)") + toSafeMd(` [${refPkgname}] 已从 ${pkgname} 的 ${mark} 状态内移除。`),
+  /**
+   * @param {{ links: string[]; }} args
+   */
+  cc: ({ links }) =>
+    wrapCode("(auto-cc)") + " ping " + links.join(" ") + toSafeMd(":"),
+  /**
+   * @param {{ link: string; pkgname: string; logLink: string; }} args `logLink` from getErrorLogDirLinkMd()
+   */
+  failingPing: ({ link, pkgname, logLink }) =>
+    _safemd`${wrapCode("(auto-mark)")} ping ${link}: ${toSafeMd(pkgname)} ${logLink}`,
+  /**
+   * @param {{ pkgname: string; logLink: string; }} args
+   */
+  failingMarked: ({ pkgname, logLink }) =>
+    _safemd`${wrapCode("(auto-mark)")} ${toSafeMd(pkgname)} 已被自动标记为 ${logLink}`,
+  /**
+   * @param {{ pkgname: string; marks: MarkedInterface[]; }} args
+   */
+  markAdded: ({ pkgname, marks }) =>
+    wrapCode("(auto-mark)") + toSafeMd(` ${pkgname} 已被标记为 ${marks.map(({ mark, comment }) => `${mark}：${comment || "无注释"}`).join("，")}`),
+  /**
+   * @param {{ pkgname: string; marks: string[]; }} args
+   */
+  cascadeMark: ({ pkgname, marks }) =>
+    wrapCode("(auto-mark)") + toSafeMd(` ${pkgname} 将被额外添加这些标记：${marks.join(" ")}`),
+  /**
+   * @param {{ pkgname: string; marks: string[]; }} args
+   */
+  cascadeUnmark: ({ pkgname, marks }) =>
+    wrapCode("(auto-unmark)") + toSafeMd(` ${pkgname} 将被清除这些标记：${marks.join(" ")}`),
+  /**
+   * @param {{ pkgname: string; mark: string; }} args
+   */
+  unmarked: ({ pkgname, mark }) =>
+    wrapCode("(auto-unmark)") + toSafeMd(` ${pkgname} 不再被标记为 ${mark}`),
+  /**
+   * @param {{ oldLink: string; newLink: string; pkgname: string; oldAlias: string; newAlias: string; }} args
+   */
+  reassigned: ({ oldLink, newLink, pkgname, oldAlias, newAlias }) =>
+    wrapCode("(reassign)") + " ping " + oldLink + " " + newLink + toSafeMd(`: ${pkgname} 已从 ${oldAlias} 转交给 ${newAlias}`),
+  /**
+   * @param {{ link: string; pkgnames: string[]; days: number; }} args
+   */
+  staleReminder: ({ link, pkgnames, days }) =>
+    wrapCode("(stale-reminder)") + " ping " + link + toSafeMd(`: ${pkgnames.join(" ")} 已认领超过 ${days} 天，还在处理吗？`),
+  /**
+   * @param {{ days: number; comment: string; }} args
+   * @returns {string} plain text, also kept in the history
+   */
+  autoDropReason: ({ days, comment }) => `认领 ${days} 天且标记为 stuck：${comment}`,
+  /**
+   * @param {{ link: string; pkgname: string; reason: string; }} args
+   */
+  autoDrop: ({ link, pkgname, reason }) =>
+    wrapCode("(auto-drop)") + " ping " + link + toSafeMd(`: ${pkgname} ${reason}，已自动释放`),
+  /**
+   * @param {{ ownerLink: string; requesterLink: string; pkgname: string; }} args
+   */
+  addConflict: ({ ownerLink, requesterLink, pkgname }) =>
+    _safemd`${wrapCode("(add-conflict)")} ping ${ownerLink}: ${requesterLink} 也想认领 ${toSafeMd(pkgname)}`,
+  /**
+   * @param {WeeklyReportArgs} args
+   */
+  weeklyReport: ({ from, to, closed, newlyFailing, oldest }) =>
+    wrapCode("(weekly-report)") + toSafeMd(` ${from} ~ ${to}\n` + [
+      `本周释放：${closed.length > 0 ? "" : "无"}`,
+      ...closed.map(({ alias, count }) => `- ${alias === null ? "未知" : alias}：${count}`),
+      `新标记为 failing：${newlyFailing.length > 0 ? newlyFailing.join(" ") : "无"}`,
+      `认领最久：${oldest.length > 0 ? "" : "无"}`,
+      ...oldest.map(({ pkgname, alias, days }) => `- ${pkgname}（${alias}，${days} 天）`),
+    ].join("\n")),
+};
+
+/**
+ * @type {typeof zh}
+ */
+const en = {
+  deleteStatuses: {
+    ftbfs: "has been built",
+    leaf: "has left the leaf list",
+    rotted: "has left the rotted list",
+  },
+  mergedPing: ({ link, pkgname, status }) =>
+    wrapCode("(auto-merge)") + " ping " + link + toSafeMd(`: ${pkgname} ${en.deleteStatuses[status]}`),
+  mergedPingBatch: ({ link, groups }) =>
+    wrapCode("(auto-merge)") + " ping " + link +
+    toSafeMd(`: ${groups.map(({ pkgnames, status }) => `${pkgnames.join(" ")} ${en.deleteStatuses[status]}`).join("; ")}`),
+  mergeFailed: ({ reason, requestId }) =>
+    wrapCode("(auto-merge)") + toSafeMd(` failed: ${reason}${requestId ? ` (request ${requestId})` : ""}`),
+  mergedUnmark: ({ pkgname, status, mark }) =>
+    wrapCode("(auto-unmark)") + toSafeMd(` ${pkgname} ${en.deleteStatuses[status]}, no longer marked as ${mark}`),
+  mergedRefUnmark: ({ pkgname, refPkgname, status, mark }) =>
+    wrapCode("(auto-unmark)") + toSafeMd(` ${pkgname} is no longer marked as ${mark}, as ${refPkgname} ${en.deleteStatuses[status]}`),
+  mergedRefRemoved: ({ pkgname, refPkgname, mark }) =>
+    wrapCode("(auto-mark)") + toSafeMd(` [${refPkgname}] has been removed from the ${mark} mark of ${pkgname}.`),
+  cc: ({ links }) =>
+    wrapCode("(auto-cc)") + " ping " + links.join(" ") + toSafeMd(":"),
+  failingPing: ({ link, pkgname, logLink }) =>
+    _safemd`${wrapCode("(auto-mark)")} ping ${link}: ${toSafeMd(pkgname)} ${logLink}`,
+  failingMarked: ({ pkgname, logLink }) =>
+    _safemd`${wrapCode("(auto-mark)")} ${toSafeMd(pkgname)} has been marked as ${logLink}`,
+  markAdded: ({ pkgname, marks }) =>
+    wrapCode("(auto-mark)") + toSafeMd(` ${pkgname} has been marked as ${marks.map(({ mark, comment }) => `${mark}: ${comment || "no comment"}`).join(", ")}`),
+  cascadeMark: ({ pkgname, marks }) =>
+    wrapCode("(auto-mark)") + toSafeMd(` ${pkgname} will also be marked as ${marks.join(" ")}`),
+  cascadeUnmark: ({ pkgname, marks }) =>
+    wrapCode("(auto-unmark)") + toSafeMd(` ${pkgname} will no longer be marked as ${marks.join(" ")}`),
+  unmarked: ({ pkgname, mark }) =>
+    wrapCode("(auto-unmark)") + toSafeMd(` ${pkgname} is no longer marked as ${mark}`),
+  reassigned: ({ oldLink, newLink, pkgname, oldAlias, newAlias }) =>
+    wrapCode("(reassign)") + " ping " + oldLink + " " + newLink + toSafeMd(`: ${pkgname} has been handed over from ${oldAlias} to ${newAlias}`),
+  staleReminder: ({ link, pkgnames, days }) =>
+    wrapCode("(stale-reminder)") + " ping " + link + toSafeMd(`: you have held ${pkgnames.join(" ")} for more than ${days} days, still working on them?`),
+  autoDropReason: ({ days, comment }) => `held for ${days} days and marked as stuck: ${comment}`,
+  autoDrop: ({ link, pkgname, reason }) =>
+    wrapCode("(auto-drop)") + " ping " + link + toSafeMd(`: ${pkgname} ${reason}, released automatically`),
+  addConflict: ({ ownerLink, requesterLink, pkgname }) =>
+    _safemd`${wrapCode("(add-conflict)")} ping ${ownerLink}: ${requesterLink} would like to take ${toSafeMd(pkgname)} too`,
+  weeklyReport: ({ from, to, closed, newlyFailing, oldest }) =>
+    wrapCode("(weekly-report)") + toSafeMd(` ${from} ~ ${to}\n` + [
+      `Released this week:${closed.length > 0 ? "" : " none"}`,
+      ...closed.map(({ alias, count }) => `- ${alias === null ? "unknown" : alias}: ${count}`),
+      `Newly marked as failing: ${newlyFailing.length > 0 ? newlyFailing.join(" ") : "none"}`,
+      `Held the longest:${oldest.length > 0 ? "" : " none"}`,
+      ...oldest.map(({ pkgname, alias, days }) => `- ${pkgname} (${alias}, ${days} days)`),
+    ].join("\n")),
+};
+
+const BUNDLES = { zh, en };
+
+/**
+ * @param {string} lang
+ * @returns {typeof zh}
+ */
+function getMessages(lang) {
+  return BUNDLES[lang];
+}
+
+module.exports = {
+  LANGUAGES: Object.keys(BUNDLES),
+  getMessages,
+};
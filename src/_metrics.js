@@ -0,0 +1,83 @@
+//@ts-check
+
+/**
+ * a minimal exporter of the Prometheus text format, only counters and gauges are needed here
+ */
+
+/**
+ * @type {Map<string, { type: "counter" | "gauge"; help: string; values: Map<string, number>; }>}
+ */
+const registry = new Map();
+
+/**
+ * @param {"counter" | "gauge"} type
+ * @param {string} name
+ * @param {string} help
+ */
+function define(type, name, help) {
+  if(!registry.has(name)) {
+    registry.set(name, { type, help, values: new Map() });
+  }
+}
+
+/**
+ * @param {Record<string, string | number>} labels
+ */
+function labelsToString(labels) {
+  const keys = Object.keys(labels).sort();
+  if(keys.length === 0) {
+    return "";
+  }
+  const pairs = keys.map(key => {
+    const value = String(labels[key]).replace(/\\/g, "\\\\").replace(/\n/g, "\\n").replace(/"/g, "\\\"");
+    return `${key}="${value}"`;
+  });
+  return "{" + pairs.join(",") + "}";
+}
+
+/**
+ * @param {string} name
+ * @param {Record<string, string | number>} [labels]
+ * @param {number} [value]
+ */
+function inc(name, labels = {}, value = 1) {
+  const metric = registry.get(name);
+  if(!metric) {
+    throw new Error(`metric ${name} is not defined`);
+  }
+  const key = labelsToString(labels);
+  metric.values.set(key, (metric.values.get(key) || 0) + value);
+}
+
+/**
+ * @param {string} name
+ * @param {number} value
+ * @param {Record<string, string | number>} [labels]
+ */
+function set(name, value, labels = {}) {
+  const metric = registry.get(name);
+  if(!metric) {
+    throw new Error(`metric ${name} is not defined`);
+  }
+  metric.values.set(labelsToString(labels), value);
+}
+
+function render() {
+  let text = "";
+  for(const [name, metric] of registry) {
+    text += `# HELP ${name} ${metric.help}\n`;
+    text += `# TYPE ${name} ${metric.type}\n`;
+    for(const [labels, value] of metric.values) {
+      text += `${name}${labels} ${value}\n`;
+    }
+  }
+  return text;
+}
+
+module.exports = {
+  counter: (/** @type {string} */ name, /** @type {string} */ help) => define("counter", name, help),
+  gauge: (/** @type {string} */ name, /** @type {string} */ help) => define("gauge", name, help),
+  inc,
+  set,
+  render,
+};
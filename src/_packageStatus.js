@@ -0,0 +1,53 @@
+//@ts-check
+
+/**
+ * the work list of /pkg. Kept out of utils.js, which loads db/ once required, so that it can be tested on its own
+ */
+
+const { getDayNumber } = require("./_time");
+
+/**
+ * @typedef {import("./utils").PackageInterface} PackageInterface
+ */
+
+/**
+ * @param {{ userid: number; username: string | undefined; packages: PackageInterface[]; }[]} status
+ * @param {"oldest" | "newest"} sort by assignedAt, of the packages and of the packagers by their oldest (or newest) package
+ * @param {(uid: number) => string} getAlias
+ * @param {number} now ms since epoch, `daysAssigned` counts up to its day
+ */
+function sortPackageStatus(status, sort, getAlias, now) {
+  const sign = sort === "newest" ? -1 : 1;
+  const today = getDayNumber(now);
+  /**
+   * @type {{ alias: string; packages: string[]; assignedAt: Record<string, string>; daysAssigned: Record<string, number>; }[]}
+   */
+  const ret = [];
+  const sortedStatus = status.map(user => ({
+    userid: user.userid,
+    packages: user.packages.slice().sort((pkg1, pkg2) => sign * (pkg1.assignedAt - pkg2.assignedAt)),
+  }));
+  /**
+   * @param {{ packages: PackageInterface[]; }} user with sorted packages
+   */
+  function sortKey(user) {
+    // packagers without packages go last
+    return user.packages.length > 0 ? sign * user.packages[0].assignedAt : Number.MAX_SAFE_INTEGER;
+  }
+  sortedStatus.sort((user1, user2) => sortKey(user1) - sortKey(user2));
+  for(const user of sortedStatus) {
+    ret.push({
+      alias: getAlias(user.userid),
+      packages: user.packages.map(pkg => pkg.name),
+      // by pkgname, in UTC ISO 8601; a map so that `packages` keeps being a list of names
+      assignedAt: Object.fromEntries(user.packages.map(pkg => [pkg.name, new Date(pkg.assignedAt).toISOString()])),
+      // calendar days in UTC+8, 0 for packages assigned today
+      daysAssigned: Object.fromEntries(user.packages.map(pkg => [pkg.name, today - getDayNumber(pkg.assignedAt)])),
+    });
+  }
+  return ret;
+}
+
+module.exports = {
+  sortPackageStatus,
+};
@@ -0,0 +1,37 @@
+//@ts-check
+
+/**
+ * checks of pkgnames from commands and the HTTP API. Kept out of utils.js, which loads db/ once required,
+ * so that they can be tested on their own
+ */
+
+const PKGNAME_MAX_LENGTH = 128;
+
+/**
+ * @param {string} pkgname
+ * @description Arch package name rules: lowercase alphanumerics and `@ . _ + -`, not starting with `-` or `.`
+ */
+function isValidPkgname(pkgname) {
+  return pkgname.length <= PKGNAME_MAX_LENGTH && /^[a-z0-9@_+][a-z0-9@._+-]*$/.test(pkgname);
+}
+
+/**
+ * @param {string} segment a percent-encoded path segment
+ * @returns {string | null} the decoded pkgname, null if the encoding is malformed or the pkgname is invalid,
+ * e.g. `%2F` decoding to a `/`
+ */
+function decodePkgnameSegment(segment) {
+  let pkgname;
+  try {
+    pkgname = decodeURIComponent(segment);
+  } catch(e) {
+    return null;
+  }
+  return isValidPkgname(pkgname) ? pkgname : null;
+}
+
+module.exports = {
+  PKGNAME_MAX_LENGTH,
+  isValidPkgname,
+  decodePkgnameSegment,
+};
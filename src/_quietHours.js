@@ -0,0 +1,34 @@
+//@ts-check
+
+/**
+ * quiet hours of notifications and stale reminders. Kept out of utils.js, which loads db/ once required,
+ * so that they can be tested on their own
+ */
+
+const { TZ } = require("./_time");
+
+/**
+ * @param {string} [hours] like "22-8", for 22:00 to 7:59 (UTC+8, the same as the logs), as validated by _config.js
+ * @returns {[number, number] | null} null if blank or not a valid window, which turns quiet hours off
+ */
+function parseQuietHours(hours) {
+  const match = /^([01]?\d|2[0-3])-([01]?\d|2[0-3])$/.exec((hours || "").trim());
+  return match ? [Number(match[1]), Number(match[2])] : null;
+}
+
+/**
+ * @param {Date} date
+ * @param {[number, number] | null} quietHours from parseQuietHours(), the window may wrap around midnight,
+ * and is empty if both ends are the same hour
+ */
+function isQuietHour(date, quietHours) {
+  if(quietHours === null) return false;
+  const [start, end] = quietHours;
+  const hour = (date.getUTCHours() + TZ + 24) % 24;
+  return start <= end ? start <= hour && hour < end : hour >= start || hour < end;
+}
+
+module.exports = {
+  parseQuietHours,
+  isQuietHour,
+};
@@ -0,0 +1,105 @@
+//@ts-check
+
+/**
+ * retries of failed Telegram sends. Kept out of plct-archrv-bot.js so that they can be tested on their own
+ */
+
+const verb = require("./_verbose");
+
+/**
+ * @param {any} err error thrown by `bot.sendMessage()`
+ * @returns {number | null} milliseconds to wait, as requested by a 429 response
+ */
+function getRetryAfter(err) {
+  const body = err && err.response && err.response.body;
+  if(body && body.parameters && typeof body.parameters.retry_after === "number") {
+    return body.parameters.retry_after * 1000;
+  }
+  return null;
+}
+
+/**
+ * @param {any} err error thrown by `bot.sendMessage()`
+ * @description timeouts, connection errors, 5xx and 429 may pass on their own,
+ * other 4xx (e.g. a bad chat id) would fail the same way again
+ */
+function isTransientSendError(err) {
+  // EFATAL is what node-telegram-bot-api throws when the request never got a response
+  if(err && err.code === "EFATAL") return true;
+  const response = err && err.response;
+  const statusCode = response && (response.statusCode || (response.body && response.body.error_code));
+  return typeof statusCode === "number" && (statusCode >= 500 || statusCode === 429);
+}
+
+/**
+ * @param {number} ms
+ * @returns {Promise<void>}
+ */
+function wait(ms) {
+  return new Promise(res => setTimeout(res, ms));
+}
+
+/**
+ * @template T
+ * @typedef RetrySendOptions
+ * @prop {number} maxRetries
+ * @prop {number} retryDelay ms before the first retry, doubled after every one
+ * @prop {number} maxRetryAfter ms, longer 429 retry_afters are handed to `requeue` instead of waited for
+ * @prop {(retryAfter: number) => Promise<T>} requeue
+ * @prop {(err: any) => void} [onError] called with the error of every failed retry
+ * @prop {(lastError: any) => void} [onGiveUp] called once all retries failed, before rejecting
+ * @prop {(ms: number) => Promise<void>} [delayFn] waits between attempts, replaced in tests
+ * @prop {() => number} [random] for the jitter, replaced in tests
+ */
+
+/**
+ * @template T
+ * @param {() => Promise<T>} send
+ * @param {any} lastError the error of the failed attempt
+ * @param {RetrySendOptions<T>} options
+ * @returns {Promise<T>}
+ * @description Retry up to `maxRetries` times with exponential backoff and jitter.
+ * 429 responses are retried after their `retry_after` instead, or requeued when that is longer than
+ * `maxRetryAfter`, so that a flood limit delays the message rather than loses it. The first retry is always made,
+ * as the caller may have changed the options to fix a MarkdownV2 error, later ones only after transient errors.
+ * The error finally thrown tells how many attempts were made.
+ */
+async function retrySend(send, lastError, options) {
+  const { maxRetries, retryDelay, maxRetryAfter, requeue, onError = () => {}, onGiveUp = () => {} } = options;
+  const { delayFn = wait, random = Math.random } = options;
+  let attempts = 1;
+  for(let attempt = 1; attempt <= maxRetries; attempt++) {
+    if(attempt > 1 && !isTransientSendError(lastError)) {
+      verb(retrySend, "not retrying, the error is not transient");
+      break;
+    }
+    const retryAfter = getRetryAfter(lastError);
+    if(retryAfter !== null && retryAfter > maxRetryAfter) {
+      verb(retrySend, "retry_after is", retryAfter, "ms, queueing the message again");
+      return requeue(retryAfter);
+    }
+    const backoff = retryDelay * 2 ** (attempt - 1);
+    // up to 50% more, so that messages failed together don't retry together
+    const delay = retryAfter !== null ? retryAfter : Math.round(backoff * (1 + random() / 2));
+    verb(retrySend, "waiting for", delay, "ms before retry", attempt, "of", maxRetries);
+    await delayFn(delay);
+    attempts++;
+    try {
+      return await send();
+    } catch(err) {
+      onError(err);
+      lastError = err;
+    }
+  }
+  onGiveUp(lastError);
+  if(lastError instanceof Error) {
+    lastError.message += ` (gave up after ${attempts} attempts)`;
+  }
+  throw lastError;
+}
+
+module.exports = {
+  getRetryAfter,
+  isTransientSendError,
+  retrySend,
+};
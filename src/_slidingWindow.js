@@ -0,0 +1,54 @@
+//@ts-check
+
+/**
+ * the per-token limit of /delete calls. Kept out of plct-archrv-bot.js so that it can be tested on its own
+ */
+
+class SlidingWindowLimiter {
+  /**
+   * @param {number} limit calls allowed per key within the window
+   * @param {number} window in ms
+   * @param {() => number} [now] the clock, replaced in tests
+   */
+  constructor(limit, window, now = Date.now) {
+    this.limit = limit;
+    this.window = window;
+    this.now = now;
+    /**
+     * @type {Map<string, number[]>} key -> timestamps of the calls within the window
+     */
+    this.calls = new Map();
+  }
+
+  /**
+   * @param {string} key
+   * @returns {number | null} null if the call is allowed and counted, otherwise seconds until it would be,
+   * for the Retry-After of a 429
+   */
+  take(key) {
+    const now = this.now();
+    const timestamps = (this.calls.get(key) || []).filter(time => time > now - this.window);
+    this.calls.set(key, timestamps);
+    if(timestamps.length < this.limit) {
+      timestamps.push(now);
+      return null;
+    }
+    return Math.ceil((timestamps[0] + this.window - now) / 1000);
+  }
+
+  /**
+   * @description drop keys without calls in the window, so that the map doesn't grow forever
+   */
+  prune() {
+    const now = this.now();
+    for(const [key, timestamps] of this.calls) {
+      if(timestamps.length === 0 || timestamps[timestamps.length - 1] <= now - this.window) {
+        this.calls.delete(key);
+      }
+    }
+  }
+}
+
+module.exports = {
+  SlidingWindowLimiter,
+};
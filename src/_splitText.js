@@ -0,0 +1,69 @@
+//@ts-check
+
+/**
+ * @param {string} line
+ * @param {number} limit
+ * @returns {number} where to cut `line`, at most `limit`: before the last space, never inside a link or after a `\\`
+ */
+function findLineCut(line, limit) {
+  // links and inline code, which break when cut in half
+  const links = Array.from(line.matchAll(/\[[^\]]*\]\([^)]*\)|(?<!`)`[^`]+`(?!`)/g), match => [match.index, match.index + match[0].length]);
+  const insideLink = (/** @type {number} */ index) => links.some(([start, end]) => start < index && index < end);
+  const space = line.lastIndexOf(" ", limit);
+  for(let index = space; index > 0; index = line.lastIndexOf(" ", index - 1)) {
+    if(!insideLink(index)) return index;
+  }
+  let cut = limit;
+  const link = links.find(([start, end]) => start < cut && cut < end);
+  if(link && link[0] > 0) cut = link[0];
+  while(cut > 1 && line[cut - 1] === "\\") cut--;
+  return cut;
+}
+
+/**
+ * @param {string} text markdown v2 text
+ * @param {number} limit
+ * @returns {string[]} parts of at most `limit` characters, split at line breaks if possible,
+ * with code blocks closed at the end of a part and reopened in the next one
+ */
+function splitLongText(text, limit) {
+  // room for closing and reopening a code block
+  const maxLength = limit - 8;
+  /**
+   * @type {string[]}
+   */
+  const lines = [];
+  for(let line of text.split("\n")) {
+    while(line.length > maxLength) {
+      const cut = findLineCut(line, maxLength);
+      lines.push(line.slice(0, cut));
+      line = line.slice(cut).replace(/^ /, "");
+    }
+    lines.push(line);
+  }
+  /**
+   * @type {string[]}
+   */
+  const parts = [];
+  let part = null;
+  for(const line of lines) {
+    if(part !== null && part.length + 1 + line.length <= maxLength) {
+      part += "\n" + line;
+    } else {
+      if(part !== null) parts.push(part);
+      part = line;
+    }
+  }
+  parts.push(part);
+  for(let i = 0; i < parts.length - 1; i++) {
+    if(parts[i].split("```").length % 2 === 0) {
+      parts[i] += "\n```";
+      parts[i + 1] = "```\n" + parts[i + 1];
+    }
+  }
+  return parts;
+}
+
+module.exports = {
+  splitLongText,
+};
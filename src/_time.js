@@ -0,0 +1,30 @@
+//@ts-check
+
+/**
+ * days and hours in UTC+8, the time zone of the logs and of the team. Kept out of utils.js, which loads db/
+ * once required, so that the modules tested on their own can use them
+ */
+
+const TZ = +8;  // UTC+8
+
+/**
+ * @param {number} time ms since epoch
+ * @returns {number} days since epoch in UTC+8, so that a day starts at midnight of the logs
+ */
+function getDayNumber(time) {
+  return Math.floor((time + TZ * 3600e3) / 86400e3);
+}
+
+/**
+ * @param {number} time ms since epoch
+ * @returns {Date} shifted to UTC+8, read it with getUTCDay(), getUTCHours(), toISOString() etc.
+ */
+function toLocalDate(time) {
+  return new Date(time + TZ * 3600e3);
+}
+
+module.exports = {
+  TZ,
+  getDayNumber,
+  toLocalDate,
+};
@@ -0,0 +1,132 @@
+//@ts-check
+
+const crypto = require("crypto");
+const { readFile, writeFile } = require("fs/promises");
+
+const TOKENS_FILE = __dirname + "/../config/tokens.json";
+
+/**
+ * @description scopes of the mutating HTTP APIs and /history, other reading APIs don't require a token
+ */
+const API_TOKEN_SCOPES = ["add", "mark", "delete", "reassign", "notify", "audit"];
+
+/**
+ * @typedef ApiTokenInterface
+ * @prop {string} name
+ * @prop {string} hash sha256 hex digest of the secret, also the HMAC key of signed requests
+ * @prop {string[]} scopes
+ */
+
+/**
+ * @type {ApiTokenInterface[]}
+ */
+const apiTokens = [];
+
+/**
+ * @param {string} str
+ */
+function sha256hex(str) {
+  return crypto.createHash("sha256").update(str, "utf8").digest("hex");
+}
+
+/**
+ * @description (re)load config/tokens.json, a missing file means no tokens
+ */
+async function loadApiTokens() {
+  /**
+   * @type {ApiTokenInterface[]}
+   */
+  let tokens = [];
+  try {
+    tokens = JSON.parse(await readFile(TOKENS_FILE, "utf8"));
+  } catch(e) {
+    if(e.code !== "ENOENT") throw e;
+  }
+  apiTokens.splice(0, apiTokens.length, ...tokens);
+  return apiTokens;
+}
+
+async function storeApiTokens() {
+  await writeFile(TOKENS_FILE, JSON.stringify(apiTokens, null, 2));
+}
+
+/**
+ * @param {string} a
+ * @param {string} b
+ */
+function safeEqual(a, b) {
+  const aDigest = crypto.createHash("sha256").update(a, "utf8").digest();
+  const bDigest = crypto.createHash("sha256").update(b, "utf8").digest();
+  return crypto.timingSafeEqual(aDigest, bDigest);
+}
+
+/**
+ * @param {string | string[] | null | undefined} provided
+ * @param {string | undefined} expected
+ * @description Compare API tokens in constant time. Digests are compared instead of the raw
+ * strings, since `timingSafeEqual` requires equal lengths and we don't want to leak the length.
+ */
+function verifyToken(provided, expected) {
+  if(typeof provided !== "string" || !expected) {
+    return false;
+  }
+  return safeEqual(provided, expected);
+}
+
+/**
+ * @param {string | string[] | undefined} signature hex HMAC-SHA256 of `path + timestamp`, from the X-Signature header
+ * @param {string | string[] | undefined} timestamp unix time in seconds, from the X-Timestamp header
+ * @param {string} path
+ * @param {string | undefined} secret
+ * @param {number} maxAge in seconds, requests with an older (or future) timestamp are rejected to stop replays
+ * @param {number} [now] ms since epoch
+ */
+function verifySignature(signature, timestamp, path, secret, maxAge, now = Date.now()) {
+  if(typeof signature !== "string" || typeof timestamp !== "string" || !/^\d+$/.test(timestamp) || !secret) {
+    return false;
+  }
+  if(Math.abs(now / 1000 - Number(timestamp)) > maxAge) {
+    return false;
+  }
+  const expected = crypto.createHmac("sha256", secret).update(path + timestamp, "utf8").digest("hex");
+  return verifyToken(signature.toLowerCase(), expected);
+}
+
+/**
+ * @param {any} provided
+ * @returns {ApiTokenInterface | null}
+ */
+function findApiToken(provided) {
+  if(typeof provided !== "string" || provided === "") {
+    return null;
+  }
+  const hash = sha256hex(provided);
+  // compare against every token, so that the time taken doesn't tell which one matched
+  let found = null;
+  for(const token of apiTokens) {
+    if(safeEqual(hash, token.hash) && found === null) {
+      found = token;
+    }
+  }
+  return found;
+}
+
+/**
+ * @param {any} name
+ * @returns {ApiTokenInterface | null}
+ */
+function findApiTokenByName(name) {
+  return apiTokens.find(token => token.name === name) || null;
+}
+
+module.exports = {
+  API_TOKEN_SCOPES,
+  apiTokens,
+  sha256hex,
+  loadApiTokens,
+  storeApiTokens,
+  findApiToken,
+  findApiTokenByName,
+  verifyToken,
+  verifySignature,
+};
@@ -0,0 +1,63 @@
+//@ts-check
+
+/**
+ * @typedef WeeklyReportInterface
+ * @prop {{ userid: number | null; count: number; }[]} closed packages released by every packager,
+ * by `/merge` or `/delete` but not by auto-drop, most first; `userid` is null if the history doesn't tell who held them
+ * @prop {string[]} newlyFailing packages marked as failing since then and still marked so
+ * @prop {{ userid: number; pkgname: string; assignedAt: number; }[]} oldest assignments still held, oldest first
+ */
+
+/**
+ * @param {import("./utils").HistoryInterface[]} history oldest first
+ * @param {{ userid: number; packages: import("./utils").PackageInterface[]; }[]} status
+ * @param {{ name: string; marks: { name: string; }[]; }[]} marks
+ * @param {number} since timestamp (ms) the report starts at
+ * @param {number} oldestLimit how many of the oldest assignments to list
+ * @returns {WeeklyReportInterface}
+ */
+function buildWeeklyReport(history, status, marks, since, oldestLimit) {
+  // the packager isn't recorded with drops of /delete, so follow who held every package through the history
+  /**
+   * @type {Map<string, number | null>}
+   */
+  const holders = new Map();
+  /**
+   * @type {Map<number | null, number>}
+   */
+  const closedCounts = new Map();
+  /**
+   * @type {Set<string>}
+   */
+  const failing = new Set();
+  for(const entry of history) {
+    if(entry.action === "add") {
+      holders.set(entry.pkgname, entry.actor.uid);
+    } else if(entry.action === "reassign") {
+      holders.set(entry.pkgname, Number(entry.detail.split(" -> ")[1]));
+    } else if(entry.action === "drop") {
+      if(entry.time >= since && entry.actor.via !== "auto") {
+        // only the packager can /merge
+        const userid = entry.actor.via === "bot" ? entry.actor.uid :
+          holders.has(entry.pkgname) ? holders.get(entry.pkgname) : null;
+        closedCounts.set(userid, (closedCounts.get(userid) || 0) + 1);
+      }
+      holders.delete(entry.pkgname);
+    } else if(entry.action === "mark" && entry.time >= since && entry.detail.split(" ")[0] === "failing") {
+      failing.add(entry.pkgname);
+    }
+  }
+  const oldest = status
+    .flatMap(user => user.packages.map(pkg => ({ userid: user.userid, pkgname: pkg.name, assignedAt: pkg.assignedAt })))
+    .sort((pkg1, pkg2) => pkg1.assignedAt - pkg2.assignedAt)
+    .slice(0, oldestLimit);
+  return {
+    closed: [...closedCounts].map(([userid, count]) => ({ userid, count })).sort((user1, user2) => user2.count - user1.count),
+    newlyFailing: [...failing].filter(pkgname => marks.some(pkg => pkg.name === pkgname && pkg.marks.some(mark => mark.name === "failing"))),
+    oldest,
+  };
+}
+
+module.exports = {
+  buildWeeklyReport,
+};
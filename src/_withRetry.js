@@ -0,0 +1,48 @@
+//@ts-check
+
+/**
+ * retries of db/ writes. Kept out of utils.js, which loads db/ once required, so that it can be tested on its own
+ */
+
+const verb = require("./_verbose");
+
+// errors of a briefly unavailable file system (e.g. an NFS mount), which may pass on their own
+const TRANSIENT_FS_ERRORS = ["EAGAIN", "EBUSY", "EINTR", "ESTALE", "ETIMEDOUT"];
+const FS_RETRY_ATTEMPTS = 5;
+const FS_RETRY_DELAY = 100;  // ms, doubled after every attempt
+const FS_RETRY_MAX_DELAY = 2000;
+
+/**
+ * @param {number} ms
+ * @returns {Promise<void>}
+ */
+function wait(ms) {
+  return new Promise(res => setTimeout(res, ms));
+}
+
+/**
+ * @template T
+ * @param {() => Promise<T>} fn
+ * @param {string} what used in the log
+ * @param {(ms: number) => Promise<void>} [delayFn] waits between attempts, replaced in tests
+ * @returns {Promise<T>} rejects with the last error after FS_RETRY_ATTEMPTS, or right away if it isn't transient
+ */
+async function withRetry(fn, what, delayFn = wait) {
+  for(let attempt = 1; ; attempt++) {
+    try {
+      return await fn();
+    } catch(err) {
+      if(attempt >= FS_RETRY_ATTEMPTS || !(err && TRANSIENT_FS_ERRORS.includes(err.code))) {
+        throw err;
+      }
+      const delay = Math.min(FS_RETRY_DELAY * 2 ** (attempt - 1), FS_RETRY_MAX_DELAY);
+      verb(withRetry, what, "failed with", err.code, "retrying in", delay, "ms");
+      await delayFn(delay);
+    }
+  }
+}
+
+module.exports = {
+  FS_RETRY_ATTEMPTS,
+  withRetry,
+};
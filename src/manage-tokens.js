@@ -0,0 +1,66 @@
+//@ts-check
+"use strict";
+
+// usage:
+//   node src/manage-tokens.js list
+//   node src/manage-tokens.js add <name> <scope>...
+//   node src/manage-tokens.js revoke <name>
+// send /reloadtokens to the bot afterwards
+
+const crypto = require("crypto");
+const {
+  API_TOKEN_SCOPES,
+  apiTokens,
+  sha256hex,
+  loadApiTokens,
+  storeApiTokens,
+  findApiTokenByName,
+} = require("./_tokens");
+
+function usage() {
+  console.error("usage: manage-tokens.js list | add <name> <scope>... | revoke <name>");
+  console.error("available scopes:", API_TOKEN_SCOPES.join(", "));
+  process.exit(1);
+}
+
+(async function main() {
+  const [command, name, ...scopes] = process.argv.slice(2);
+  await loadApiTokens();
+  switch(command) {
+    case "list":
+      for(const token of apiTokens) {
+        console.log(token.name, token.scopes.join(","));
+      }
+      break;
+    case "add": {
+      if(!name || scopes.length === 0) usage();
+      const unknownScopes = scopes.filter(scope => !API_TOKEN_SCOPES.includes(scope));
+      if(unknownScopes.length > 0) {
+        console.error("unknown scopes:", unknownScopes.join(", "));
+        usage();
+      }
+      if(findApiTokenByName(name)) {
+        console.error(`token ${name} already exists, revoke it first`);
+        process.exit(1);
+      }
+      const secret = crypto.randomBytes(32).toString("hex");
+      apiTokens.push({ name, hash: sha256hex(secret), scopes });
+      await storeApiTokens();
+      // only the hash is stored, so this is the only chance to see the secret
+      console.log(secret);
+      break;
+    }
+    case "revoke": {
+      const token = findApiTokenByName(name);
+      if(!token) {
+        console.error(`token ${name} not found`);
+        process.exit(1);
+      }
+      apiTokens.splice(apiTokens.indexOf(token), 1);
+      await storeApiTokens();
+      break;
+    }
+    default:
+      usage();
+  }
+})();
@@ -0,0 +1,181 @@
+//@ts-check
+"use strict";
+
+// usage:
+//   node src/manage.js migrate
+//   node src/manage.js import <file>
+//   node src/manage.js send-test-message <text>
+// migrate and import rewrite db/, so stop the bot first; `node src/plct-archrv-bot.js` serves as before
+
+const { readFile } = require("fs/promises");
+const lock = require("lockfile");
+const { getConfigPath, validateConfig } = require("./_config");
+
+require("dotenv").config({
+  path: getConfigPath(),
+});
+
+function usage() {
+  console.error("usage: manage.js migrate | import <file> | send-test-message <text>");
+  process.exit(1);
+}
+
+/**
+ * @param {string} message
+ */
+function fail(message) {
+  console.error(message);
+  process.exit(1);
+}
+
+async function lockDb() {
+  try {
+    await new Promise((res, rej) => {
+      lock.lock("plct.lock", function (err) {
+        err ? rej(err) : res();
+      });
+    });
+  } catch(e) {
+    fail(`failed to lock plct.lock, is the bot running? ${e.message}`);
+  }
+}
+
+/**
+ * @param {typeof import("./utils")} utils
+ */
+function storeDb(utils) {
+  utils.storePackageStatusSync();
+  utils.storePackageMarksSync();
+  utils.storePackageHistorySync();
+}
+
+/**
+ * @param {typeof import("./utils")} utils
+ * @param {string} alias as in the output of /pkg, `uid=123` for packagers without an alias
+ * @returns {number | null}
+ */
+function resolveAlias(utils, alias) {
+  if(typeof alias !== "string") return null;
+  const match = alias.match(/^uid=(\d+)$/);
+  return match ? Number(match[1]) : utils.findUserIdByAlias(alias);
+}
+
+/**
+ * @param {typeof import("./utils")} utils
+ * @param {any} dump the JSON of /pkg
+ * @returns {string[]} what was skipped and why
+ */
+function importDump(utils, dump) {
+  const { packageStatus, packageMarks, recordHistory, getAlias, getMentionLink } = utils;
+  /**
+   * @type {import("./utils").HistoryActorInterface}
+   */
+  const actor = { via: "cli", uid: null };
+  const skipped = [];
+  for(const unit of dump.workList) {
+    const userId = resolveAlias(utils, unit.alias);
+    if(userId === null) {
+      skipped.push(`unknown packager ${unit.alias}, not assigning ${unit.packages.join(" ")}`);
+      continue;
+    }
+    for(const pkgname of unit.packages) {
+      const ownerId = utils.findUserIdByPackage(pkgname);
+      if(ownerId !== null) {
+        skipped.push(`${pkgname} is already assigned to ${getAlias(ownerId)}`);
+        continue;
+      }
+      let user = packageStatus.find(user => user.userid === userId);
+      if(!user) {
+        user = { userid: userId, username: undefined, packages: [] };
+        packageStatus.push(user);
+      }
+      user.packages.push({ name: pkgname, lastActive: Date.now(), assignedAt: Date.now() });
+      recordHistory(pkgname, "add", actor, "imported");
+    }
+  }
+  const availableMarks = utils.getAvailableMarks();
+  for(const pkg of dump.markList) {
+    for(const mark of pkg.marks) {
+      if(!availableMarks.includes(mark.name)) {
+        skipped.push(`unknown mark ${mark.name} of ${pkg.name}`);
+        continue;
+      }
+      if(utils.getPackageMarkNamesByPkgname(pkg.name).includes(mark.name)) {
+        skipped.push(`${pkg.name} is already marked as ${mark.name}`);
+        continue;
+      }
+      let target = packageMarks.find(obj => obj.name === pkg.name);
+      if(!target) {
+        target = { name: pkg.name, marks: [] };
+        packageMarks.push(target);
+        packageMarks.sort((pkg1, pkg2) => utils.strcmp(pkg1.name, pkg2.name));
+      }
+      const userId = mark.by ? resolveAlias(utils, mark.by.alias) : null;
+      const comment = mark.comment || "";
+      target.marks.push({
+        name: mark.name,
+        by: userId === null ? null : { url: getMentionLink(userId, null, getAlias(userId)), uid: userId, alias: getAlias(userId) },
+        comment,
+        markedAt: mark.markedAt || Date.now(),
+      });
+      target.marks.sort((a, b) => a.name > b.name ? 1 : a.name === b.name ? 0 : -1);
+      recordHistory(pkg.name, "mark", actor, comment ? `${mark.name} ${comment}` : mark.name);
+    }
+  }
+  return skipped;
+}
+
+(async function main() {
+  const [command, arg] = process.argv.slice(2);
+  switch(command) {
+    case "migrate": {
+      await lockDb();
+      // loading upgrades the old schemas, the same as when the bot starts
+      const utils = require("./utils");
+      storeDb(utils);
+      lock.unlockSync("plct.lock");
+      console.log(`stored ${utils.packageStatus.length} packagers, ${utils.packageMarks.length} marked packages and ${utils.packageHistory.length} history entries`);
+      break;
+    }
+    case "import": {
+      if(!arg) usage();
+      let dump;
+      try {
+        dump = JSON.parse(await readFile(arg, "utf8"));
+      } catch(e) {
+        fail(`failed to read ${arg}: ${e.message}`);
+      }
+      if(!dump || !Array.isArray(dump.workList) || !Array.isArray(dump.markList)) {
+        fail(`${arg} is not the JSON of /pkg, expecting workList and markList`);
+      }
+      await lockDb();
+      const utils = require("./utils");
+      await utils.loadAlias();
+      const historyLength = utils.packageHistory.length;
+      const skipped = importDump(utils, dump);
+      storeDb(utils);
+      lock.unlockSync("plct.lock");
+      skipped.forEach(reason => console.error("skipped:", reason));
+      console.log(`imported ${utils.packageHistory.length - historyLength} assignments and marks, skipped ${skipped.length}`);
+      break;
+    }
+    case "send-test-message": {
+      if(!arg) usage();
+      const configErrors = validateConfig(process.env);
+      if(configErrors.length > 0) {
+        fail(configErrors.map(error => `${getConfigPath()}: ${error}`).join("\n"));
+      }
+      const TelegramBot = require("node-telegram-bot-api");
+      const bot = new TelegramBot(process.env["PLCT_BOT_TOKEN"], { polling: false });
+      try {
+        const sent = await bot.sendMessage(process.env["PLCT_CHAT_ID"], arg);
+        console.log(JSON.stringify(sent, null, 2));
+      } catch(e) {
+        fail(`failed to send the message: ${e.message}`);
+      }
+      break;
+    }
+    default:
+      usage();
+  }
+})();
@@ -4,10 +4,24 @@
 
 ;(async () => {
 
+const { getConfigPath, getDefaultConfig, validateConfig } = require("./_config");
+
+if(process.argv.includes("--print-default-config")) {
+  process.stdout.write(getDefaultConfig());
+  process.exit(0);
+}
+
 require("dotenv").config({
-  path: "./config/.env",
+  path: getConfigPath(),
 });
 
+// fail before taking the lock and talking to telegram
+const configErrors = validateConfig(process.env);
+if(configErrors.length > 0) {
+  configErrors.forEach(error => console.error("[ERROR]", `${getConfigPath()}:`, error));  // eslint-disable-line
+  process.exit(1);
+}
+
 const TelegramBot = require("node-telegram-bot-api");
 const { inspect } = require("util");
 const crypto = require("crypto");
@@ -16,6 +30,17 @@ const lock = require("lockfile");
 
 console.log("[INFO]", "PID", process.pid);  // eslint-disable-line
 const verb = require("./_verbose");
+const metrics = require("./_metrics");
+const { loadApiTokens, findApiToken, findApiTokenByName, verifyToken, verifySignature } = require("./_tokens");
+const { HttpError, respondError } = require("./_httpError");
+const { renderDashboard } = require("./_dashboard");
+const { decodePkgnameSegment } = require("./_pkgname");
+const { SlidingWindowLimiter } = require("./_slidingWindow");
+const { getRetryAfter, isTransientSendError, retrySend } = require("./_sendRetry");
+const { KeyedLock } = require("./_keyedLock");
+const { getMarkItems, planMarks } = require("./_markRequest");
+const { IdempotencyCache } = require("./_idempotency");
+const mergePlan = require("./_mergePlan");
 
 try {
   const lockPromise = new Promise((res, rej) => {
@@ -30,50 +55,110 @@ try {
   process.exit(1);
 }
 
-require("async-exit-hook")(() => {
+const exitHook = require("async-exit-hook");
+const SHUTDOWN_GRACE_PERIOD = Number(process.env["PLCT_SHUTDOWN_GRACE_PERIOD"] || 10e3);
+// leave some time for storing and unlocking after the grace period
+exitHook.forceExitTimeout(SHUTDOWN_GRACE_PERIOD + 5e3);
+
+/**
+ * @type {(() => Promise<void>) | null} set once the http server is up
+ */
+let drainBeforeExit = null;
+
+function storeAndUnlock() {
   try {
     if(messageQueue.length) {
-      console.log("[INFO]", "messages that fail to send:");
+      console.log("[INFO]", "messages that fail to send are kept in db/pendingNotifications.json:");
       console.log(messageQueue);
+      // sent after the next start
+      localUtils.pendingNotifications.push(...messageQueue.map(msg => ({
+        chatId: msg.chatId, text: msg.text, options: msg.options, failedAt: Date.now(), attempts: 0,
+      })));
     }
     localUtils.storePackageStatusSync();
     localUtils.storePackageMarksSync();
-    verb("stored package status and marks");
+    localUtils.storePackageHistorySync();
+    localUtils.storePendingNotificationsSync();
+    localUtils.storePackageMessagesSync();
+    verb("stored package status, marks, history, pending notifications and ping message ids");
     lock.unlockSync("plct.lock");
     verb(lock.unlockSync, "successfully unlocked plct.lock");
   } catch (e) {
     verb(lock.unlockSync, "failed to unlock lockfile:", e);
   }
+}
+
+exitHook((done) => {
+  if(!drainBeforeExit) {
+    storeAndUnlock();
+    done();
+    return;
+  }
+  drainBeforeExit().catch(e => verb("failed to drain before exit:", e)).then(() => {
+    storeAndUnlock();
+    done();
+  });
 });
 
 const localUtils = require("./utils");
+const { getMessages } = require("./_messages");
+const { assignPackage, movePackage, shouldPingOwner } = require("./_assign");
+const { buildWeeklyReport } = require("./_weeklyReport");
+// notifications only, the replies to bot commands stay in Chinese
+const tgMessages = getMessages(process.env["PLCT_LANG"] || "zh");
 
 const {
-  _safemd,
   defer,
+  DELETE_STATUSES,
   equal,
   getAlias,
+  setAlias,
+  findUserIdByPackager,
   escapeRegExp,
+  isValidPkgname,
   marksToStringArr,
   getMentionLink,
   getCurrentTimeStr,
+  parseQuietHours,
+  isQuietHour,
   getErrorLogDirLinkMd,
   getMarkConfig,
   getAvailableMarks,
+  getClearOnMergeMarks,
   findUserIdByPackage,
+  getPackageMarksByPkgname,
   getPackageMarkNamesByPkgname,
   findPackageMarksByMarkName,
   findPackageMarksByMarkNamesAndComment,
+  findStalePackageMarks,
+  findStaleAssignments,
   toSafeMd,
   toSafeCode,
   wrapCode,
+  splitLongText,
+  getClientIp,
   sleep,
   strcmp,
   packageStatus,
   storePackageStatus,
   packageMarks,
   storePackageMarks,
+  storePackageHistory,
+  pendingNotifications,
+  storePendingNotifications,
+  storePackageMessages,
+  findPackageMessageId,
+  setPackageMessageId,
+  recordHistory,
+  truncateHistory,
+  historyEvents,
+  getDataVersion,
+  getPackageHistory,
+  withTimeout,
+  checkStorage,
   stripPackageStatus,
+  getDayNumber,
+  toLocalDate,
   stripPackageMarks,
 } = localUtils;
 
@@ -101,14 +186,62 @@ Array.prototype.remove = function (value) {
   return that;
 }
 
-const bot = new TelegramBot(token, { polling: true });
+// with a webhook secret, updates are pushed to POST /telegram/webhook instead of being polled
+const TELEGRAM_WEBHOOK_SECRET = process.env["PLCT_TELEGRAM_WEBHOOK_SECRET"];
+const TELEGRAM_WEBHOOK_URL = process.env["PLCT_TELEGRAM_WEBHOOK_URL"];
+
+const bot = new TelegramBot(token, { polling: !TELEGRAM_WEBHOOK_SECRET });
+if(TELEGRAM_WEBHOOK_SECRET && TELEGRAM_WEBHOOK_URL) {
+  await bot.setWebHook(TELEGRAM_WEBHOOK_URL, {
+    // @ts-ignore: not in the typings of node-telegram-bot-api yet
+    secret_token: TELEGRAM_WEBHOOK_SECRET,
+  });
+  verb("webhook set to", TELEGRAM_WEBHOOK_URL);
+}
 const BOT_ID = await bot.getMe().then((me) => me.id);
 const BOT_MENTION_LINK = getMentionLink(BOT_ID, null, "null");
 process.env["BOT_ID"] = String(BOT_ID);
 
 const ADMIN_ID = Number(process.env["PLCT_BOT_ADMIN_USERID"]);
 const CHAT_ID = process.env["PLCT_CHAT_ID"];
+/**
+ * @type {Map<string, string>} arch -> chat id, from `PLCT_ARCH_CHAT_IDS="riscv64=-100123,x86_64=-100456"`
+ */
+const ARCH_CHAT_IDS = new Map((process.env["PLCT_ARCH_CHAT_IDS"] || "").split(",").filter(pair => pair.includes("="))
+  .map(pair => /** @type {[string, string]} */ (pair.split("=").map(str => str.trim()))));
+/**
+ * @typedef {{ chatId: string; threadId: number | null; }} Destination a chat, and the forum topic in it if any
+ * @typedef {"merge" | "marks" | "admin"} Purpose
+ */
+/**
+ * @type {Map<string, Destination>} purpose -> destination, from `PLCT_CHAT_DESTINATIONS="merge=-100123:45,admin=-100456"`
+ */
+const CHAT_DESTINATIONS = new Map((process.env["PLCT_CHAT_DESTINATIONS"] || "").split(",").filter(pair => pair.includes("="))
+  .map(pair => {
+    const [purpose, destination] = pair.split("=").map(str => str.trim());
+    const [chatId, threadId] = destination.split(":");
+    return [purpose, { chatId, threadId: threadId ? Number(threadId) : null }];
+  }));
+// the token from .env has every scope, named tokens with scopes are in config/tokens.json
 const HTTP_API_TOKEN = process.env["PLCT_HTTP_API_TOKEN"];
+await loadApiTokens().catch(e => verb(loadApiTokens, "failed to load config/tokens.json:", e.message));
+const HTTP_SIGNATURE_MAX_AGE = Number(process.env["PLCT_HTTP_SIGNATURE_MAX_AGE"] || 300);
+// plaintext ?token= ends up in access logs, it is deprecated in favor of X-Signature
+const HTTP_ALLOW_QUERY_TOKEN = process.env["PLCT_HTTP_ALLOW_QUERY_TOKEN"] !== "0";
+/**
+ * @type {import("./utils").HistoryActorInterface}
+ */
+const API_ACTOR = { via: "api", uid: null };
+const HEALTH_CHECK_TIMEOUT = Number(process.env["PLCT_HEALTH_CHECK_TIMEOUT"] || 2000);
+// getMe costs API quota, so /healthz only checks telegram on ?telegram=1 unless this is set
+const HEALTHZ_CHECK_TELEGRAM = process.env["PLCT_HEALTHZ_CHECK_TELEGRAM"] === "1";
+// empty means all interfaces
+const HTTP_HOST = process.env["PLCT_HTTP_HOST"] || undefined;
+// some groups find it noisy, so telling the owner about a failed /add of their package is opt-in
+const NOTIFY_ADD_CONFLICT = process.env["PLCT_NOTIFY_ADD_CONFLICT"] === "1";
+// when set, /metrics moves from the API server to its own listener, e.g. on an internal interface
+const METRICS_PORT = process.env["PLCT_METRICS_PORT"];
+const METRICS_HOST = process.env["PLCT_METRICS_HOST"] || undefined;
 
 //  --------- initialize cache ends ----------- //
 
@@ -164,11 +297,14 @@ function onText(regexp, cb) {
    _options: TelegramBot.SendMessageOptions;
    throttle: boolean;
    timestamp: number;
+   notBefore: number;
    resolve: (value: any) => void;
    reject: (reason?: any) => void;
   }[]}
  */
 const messageQueue = [];
+// messages taken from messageQueue whose sending (including retries) has not finished yet
+let inFlightMessageCount = 0;
 
 // wrapper: push message to queue
 /**
@@ -177,9 +313,10 @@ const messageQueue = [];
  * @param {boolean} throttle
  * @param {TelegramBot.SendMessageOptions} _options
  * @param {TelegramBot.SendMessageOptions} [options]
+ * @param {number} [notBefore] timestamp (ms) before which the message is held back
  * @returns {Promise<TelegramBot.Message>}
  */
-function sendMessageWithRateLimit(chatId, text, throttle, _options, options = {}) {
+function sendMessageWithRateLimit(chatId, text, throttle, _options, options = {}, notBefore = 0) {
   return new Promise((resolve, reject) => {
     messageQueue.push({
       chatId,
@@ -188,6 +325,7 @@ function sendMessageWithRateLimit(chatId, text, throttle, _options, options = {}
       _options,
       throttle,
       timestamp: Date.now(),
+      notBefore,
       // pass resolve and reject to messageQueue's consumer
       resolve,
       reject,
@@ -195,6 +333,51 @@ function sendMessageWithRateLimit(chatId, text, throttle, _options, options = {}
   });
 }
 
+// pings asking for `disable_notification: false` are still sent silently within these hours, e.g. "22-8"
+const NOTIFICATION_QUIET_HOURS = parseQuietHours(process.env["PLCT_NOTIFICATION_QUIET_HOURS"]);
+
+/**
+ * @param {TelegramBot.SendMessageOptions} options
+ * @returns {TelegramBot.SendMessageOptions} `options`, made silent within NOTIFICATION_QUIET_HOURS.
+ * Called right before sending, so that a message queued in the evening doesn't ring at night
+ */
+function silenceInQuietHours(options) {
+  if(options.disable_notification === false && isQuietHour(new Date(), NOTIFICATION_QUIET_HOURS)) {
+    return Object.assign({}, options, { disable_notification: true });
+  }
+  return options;
+}
+
+const SENT_TEXTS_MAX_SIZE = 500;
+/**
+ * @type {Map<string, { text: string; parseMode: string | undefined; }>} "chat id:message id" -> the text as sent,
+ * since the Message of Telegram only has the rendered text. Used to append to recent messages, oldest dropped first
+ */
+const sentTexts = new Map();
+
+/**
+ * @param {TelegramBot.Message} message
+ * @param {string} text
+ * @param {TelegramBot.SendMessageOptions} options
+ * @returns {TelegramBot.Message} `message`
+ */
+function rememberSentText(message, text, options) {
+  if(message && message.chat) {
+    sentTexts.set(`${message.chat.id}:${message.message_id}`, { text, parseMode: options.parse_mode });
+    if(sentTexts.size > SENT_TEXTS_MAX_SIZE) {
+      sentTexts.delete(sentTexts.keys().next().value);
+    }
+  }
+  return message;
+}
+
+// Telegram allows about one message per second in a chat, so sends to the same chat are paced by this (ms)
+const SEND_MESSAGE_INTERVAL = Number(process.env["PLCT_SEND_MESSAGE_INTERVAL"] || 1000);
+/**
+ * @type {Map<string, number>} chat id -> when the last message was sent to it
+ */
+const lastSentAt = new Map();
+
 // consume message from queue with rate limit
 // messages are added to queue by `sendMessage()`
 async function doSendMessage() {
@@ -203,15 +386,18 @@ async function doSendMessage() {
     setTimeout(() => doSendMessage(), 0);
     return;
   }
+  const now = Date.now();
   let index = 0;
   while(index < messageQueue.length) {
-    if(!messageQueue[index].throttle) {
+    const { throttle, notBefore, chatId } = messageQueue[index];
+    // the first sendable message of each chat is its oldest, so the order within a chat is kept
+    if(!throttle && notBefore <= now && (lastSentAt.get(String(chatId)) || 0) + SEND_MESSAGE_INTERVAL <= now) {
       break;
     }
     index++;
   }
   if(index === messageQueue.length) {
-    setTimeout(() => doSendMessage(), 800);
+    setTimeout(() => doSendMessage(), 100);
     return;
   }
   let { chatId, text, options, _options, timestamp, resolve, reject } = messageQueue.splice(index, 1)[0];
@@ -221,21 +407,68 @@ async function doSendMessage() {
   verb("with options", options);
   verb("lagging behind", Date.now() - timestamp, "ms");
 
-  bot.sendMessage(chatId, text, options).then(resolve).catch((err) => {
+  inFlightMessageCount++;
+  lastSentAt.set(String(chatId), now);
+  bot.sendMessage(chatId, text, silenceInQuietHours(options)).then((message) => rememberSentText(message, text, options), (err) => {
     verb(sendMessage, err.name, inspect(err), options);
-    if(inspect(err).includes("429 Too Many Requests")) {
-      const sleepTime = parseInt(inspect(err).match(/ETELEGRAM: 429 Too Many Requests: retry after (\d+)/)[1], 10) * 1000 || 3000;
-      verb(sendMessage, "waiting for", sleepTime, "ms before retrying...");
-      sleep(sleepTime).then(() => bot.sendMessage(chatId, text, Object.assign(Object.assign({}, defaultMessageOption), _options)).then(resolve).catch(reject));
-    } else {
-      bot.sendMessage(chatId, text, Object.assign(Object.assign({}, defaultMessageOption), _options)).then(resolve).catch(reject);
+    metrics.inc("plct_telegram_send_errors_total");
+    const retryAfter = getRetryAfter(err);
+    if(retryAfter !== null && retryAfter > SEND_MESSAGE_MAX_RETRY_AFTER) {
+      // nothing wrong with the options, so keep them
+      verb(sendMessage, "retry_after is", retryAfter, "ms, queueing the message again");
+      return sendMessageWithRateLimit(chatId, text, false, _options, options, Date.now() + retryAfter);
     }
-  });
-  setTimeout(() => doSendMessage(), 800);
+    // retry with the fallback options, in case the failure is caused by MarkdownV2
+    return retrySendMessage(chatId, text, Object.assign(Object.assign({}, defaultMessageOption), _options), err);
+  }).then((message) => {
+    metrics.inc("plct_telegram_messages_sent_total");
+    return message;
+  }).then(resolve, reject).finally(() => inFlightMessageCount--);
+  // other chats don't have to wait
+  setTimeout(() => doSendMessage(), 50);
   return;
 }
 setTimeout(() => doSendMessage(), 200);
 
+metrics.counter("plct_http_requests_total", "HTTP requests by route pattern and status code");
+metrics.counter("plct_delete_total", "auto-merges triggered by /delete, by result");
+metrics.counter("plct_telegram_messages_sent_total", "messages sent successfully, after retries if any");
+metrics.counter("plct_telegram_send_errors_total", "failed sendMessage calls, retries included");
+metrics.counter("plct_telegram_messages_dropped_total", "messages given up after all retries failed");
+metrics.gauge("plct_assigned_packages", "packages in the working list");
+metrics.gauge("plct_packagers", "packagers holding at least one package");
+metrics.gauge("plct_marked_packages", "packages with at least one mark");
+metrics.gauge("plct_pending_notifications", "failed notifications waiting to be sent again");
+
+const SEND_MESSAGE_MAX_RETRIES = Number(process.env["PLCT_SEND_MESSAGE_MAX_RETRIES"] || 3);
+const SEND_MESSAGE_RETRY_DELAY = Number(process.env["PLCT_SEND_MESSAGE_RETRY_DELAY"] || 1000);
+// a longer retry_after puts the message back into the queue, instead of holding up the retries
+const SEND_MESSAGE_MAX_RETRY_AFTER = Number(process.env["PLCT_SEND_MESSAGE_MAX_RETRY_AFTER"] || 30) * 1000;
+
+/**
+ * @param {number | string} chatId
+ * @param {string} text
+ * @param {TelegramBot.SendMessageOptions} options
+ * @param {any} lastError the error of the failed attempt
+ * @returns {Promise<TelegramBot.Message>}
+ * @description retrySend() with SEND_MESSAGE_MAX_RETRIES, a 429 with a longer retry_after than
+ * SEND_MESSAGE_MAX_RETRY_AFTER puts the message back into the queue
+ */
+function retrySendMessage(chatId, text, options, lastError) {
+  return retrySend(async () => rememberSentText(await bot.sendMessage(chatId, text, silenceInQuietHours(options)), text, options), lastError, {
+    maxRetries: SEND_MESSAGE_MAX_RETRIES,
+    retryDelay: SEND_MESSAGE_RETRY_DELAY,
+    maxRetryAfter: SEND_MESSAGE_MAX_RETRY_AFTER,
+    requeue: retryAfter => sendMessageWithRateLimit(chatId, text, false, options, options, Date.now() + retryAfter),
+    onError: err => {
+      verb(retrySendMessage, err.name, inspect(err), options);
+      metrics.inc("plct_telegram_send_errors_total");
+    },
+    onGiveUp: () => metrics.inc("plct_telegram_messages_dropped_total"),
+    delayFn: sleep,
+  });
+}
+
 /**
  * @param {number} chatId
  */
@@ -257,6 +490,16 @@ function markChatThrottledMsgAsSendable(chatId) {
   return hasModified;
 }
 
+/**
+ * @param {TelegramBot.SendMessageOptions} options
+ * @description `options` without disable_notification, throttled messages differing only in it are merged
+ */
+function withoutNotification(options) {
+  const rest = Object.assign({}, options);
+  delete rest.disable_notification;
+  return rest;
+}
+
 // no (throttled) message: sleep 200ms
 // has throttled message: send per chat and sleep 20ms
 /**
@@ -301,7 +544,7 @@ async function doSendThrottleMessage() {
       text: string;
       options: TelegramBot.SendMessageOptions;
       _options: TelegramBot.SendMessageOptions;
-      resolveCallbacks: ((value: any) => void)[];
+      resolveCallbacks: ((value: TelegramBot.Message) => void)[];
       rejectCallbacks: ((reason?: any) => void)[];
     }[]}
    */
@@ -319,10 +562,16 @@ async function doSendThrottleMessage() {
         rejectCallbacks: [ currMessage.reject ],
       });
     } else {
-      if(equal(currMessage.options, mergedMessages[mergedMessages.length - 1].options) &&
-      mergedMessages[mergedMessages.length - 1].text.length + currMessage.text.length <= 4000) {
+      const lastMessage = mergedMessages[mergedMessages.length - 1];
+      if(equal(withoutNotification(currMessage.options), withoutNotification(lastMessage.options)) &&
+      lastMessage.text.length + currMessage.text.length <= 4000) {
         // options match indicates _options match, because _options is generated from options
         // messages with same options can be merged.
+        if(currMessage.options.disable_notification === false) {
+          // a ping merged with silent messages still rings
+          lastMessage.options = Object.assign({}, lastMessage.options, { disable_notification: false });
+          lastMessage._options = Object.assign({}, lastMessage._options, { disable_notification: false });
+        }
         mergedMessages[mergedMessages.length - 1].text += "\n" + currMessage.text;
         mergedMessages[mergedMessages.length - 1].resolveCallbacks.push(currMessage.resolve);
         mergedMessages[mergedMessages.length - 1].rejectCallbacks.push(currMessage.reject);
@@ -348,14 +597,20 @@ async function doSendThrottleMessage() {
   // 到这里就结束 atomic 的部分了，可以开始用 await
   for(let i = 0; i < mergedMessages.length; i++) {
     const mergedMessage = mergedMessages[i];
-    function resolveAll() {
+    /**
+     * @param {TelegramBot.Message} message every merged message resolves with it, message_id included
+     */
+    function resolveAll(message) {
       verb(resolveAll, "resolving all callbacks...");
-      mergedMessage.resolveCallbacks.forEach((resolve) => resolve());
+      mergedMessage.resolveCallbacks.forEach((resolve) => resolve(message));
     }
-    function rejectAll() {
+    /**
+     * @param {any} err
+     */
+    function rejectAll(err) {
       verb(resolveAll, "rejecting all callbacks...");
       // Oh hell no, all promises are rejected!
-      mergedMessage.rejectCallbacks.forEach((reject) => reject());
+      mergedMessage.rejectCallbacks.forEach((reject) => reject(err));
     }
     messageQueue.push({
       chatId: currChatId,
@@ -363,6 +618,7 @@ async function doSendThrottleMessage() {
       options: mergedMessage.options,
       _options: mergedMessage._options,
       timestamp: Date.now(),
+      notBefore: 0,
       throttle: false,
       resolve: resolveAll,
       reject: rejectAll,
@@ -377,40 +633,60 @@ setTimeout(() => doSendThrottleMessage(), 200);
 
 // push message to queue
 /**
- * @param {number | string} chatId
+ * @param {number | string | Destination} to a chat id, or a destination from getDestination() to post into its forum topic
  * @param {string} text
- * @param {TelegramBot.SendMessageOptions} [options]
+ * @param {TelegramBot.SendMessageOptions & { message_thread_id?: number }} [options]
  * @param {boolean} [throttle]
- * @returns {Promise<TelegramBot.Message>}
+ * @returns {Promise<TelegramBot.Message>} the sent message, whose `message_id` can be passed to editMessage().
+ * Throttled messages resolve with the message they are merged into, long ones with the last part
  */
-function sendMessage(chatId, text, options = {}, throttle = false) {
+function sendMessage(to, text, options = {}, throttle = false) {
   verb(sendMessage, "(wrapper)", text.slice(0, 25) + "...", throttle);
+  const chatId = typeof to === "object" ? to.chatId : to;
   options = Object.assign(Object.assign({}, defaultMessageOption), options);
+  if(typeof to === "object" && to.threadId !== null) {
+    // part of the options, so that throttling only merges messages of the same topic
+    options.message_thread_id = to.threadId;
+  }
   // Fallback options in case error occurs
   /**
-   * @type {TelegramBot.SendMessageOptions}
+   * @type {TelegramBot.SendMessageOptions & { message_thread_id?: number }}
    */
   const _options = {
     disable_notification: options.disable_notification,
+    message_thread_id: options.message_thread_id,
     // Fix Telegram MarkdownV2's strange parsing behavior
     // --> Just use the Markdown parser as a fallback instead of MarkdownV2
     parse_mode: options.parse_mode && options.parse_mode.startsWith("Markdown") ? "Markdown" : void 0,
   };
 
   if(text.length > 4000) {
-    let thisText = text.substring(text.length - 4000, text.length);
-    let thatText = text.substring(0, text.length - 4000);
-    if(thisText.split("```").length % 2 !== 1) {
-      thisText = "```" + thisText;
-    }
-    if(thatText.split("```").length % 2 !== 1) {
-      thatText += "```";
+    const parts = splitLongText(text, 4000);
+    if(throttle) {
+      // queued together, they are merged and sent in the order of the queue; each part is kept if it fails
+      return Promise.all(parts.map(part => sendMessage(chatId, part, options, true))).then(messages => messages[messages.length - 1]);
     }
-    return sendMessage(chatId, thatText, options).then(() => {
-      return sendMessage(chatId, thisText, options);
-    });
+    // one part after another, so that they arrive in order
+    return parts.reduce(
+      (prev, part) => prev.then(() => sendMessage(chatId, part, options)),
+      /** @type {Promise<any>} */ (Promise.resolve())
+    );
+  }
+  const sent = sendMessageWithRateLimit(chatId, text, throttle, _options, options);
+  if(throttle) {
+    // throttled messages are the notifications nobody waits for, keep them instead of losing them
+    sent.catch(err => {
+      if(!isTransientSendError(err)) {
+        // e.g. the bot was kicked or the text can't be parsed, sending it again would fail the same way
+        verb(sendMessage, "dropping the failed notification:", err.message);
+        return;
+      }
+      verb(sendMessage, "keeping the failed notification for later:", err.message);
+      pendingNotifications.push({ chatId, text, options, failedAt: Date.now(), attempts: 0 });
+      return storePendingNotifications();
+    }).catch(err => verb(sendMessage, "failed to store the pending notification:", err));
   }
-  return sendMessageWithRateLimit(chatId, text, throttle, _options, options);
+  return sent;
 }
 
 /**
@@ -436,19 +712,70 @@ function replyMessage(chatId, msgId, text, options = {}) {
 
 /**
  * @param {number | string} chatId
- * @param {string | number} msgId
+ * @param {string | number} msgId `message_id` of a message sent by the bot, e.g. from sendMessage()
  * @param {string} newText
+ * @param {TelegramBot.EditMessageTextOptions} [options] e.g. `parse_mode`
+ * @returns {Promise<TelegramBot.Message | boolean>} the edited message, or a new one sent if editing failed
  */
-function editMessage(chatId, msgId, newText) {
-  return bot.editMessageText(newText, {
+function editMessage(chatId, msgId, newText, options = {}) {
+  return bot.editMessageText(newText, Object.assign({}, options, {
     chat_id: chatId,
     message_id: Number(msgId),
-  }).catch((err) => {
+  })).catch((err) => {
     verb(editMessage, "failed to edit message due to", err.name);
-    return sendMessage(chatId, newText, defaultMessageOption);
+    return sendMessage(chatId, newText, { parse_mode: options.parse_mode });
+  });
+}
+
+/**
+ * @param {number | string} chatId
+ * @param {number} msgId `message_id` of a message in that chat, e.g. from sendMessage()
+ * @returns {Promise<boolean>} false if Telegram refused, e.g. the bot may not pin messages in that chat
+ */
+function pinMessage(chatId, msgId) {
+  return bot.pinChatMessage(chatId, msgId, { disable_notification: true }).catch((err) => {
+    verb(pinMessage, "failed to pin message due to", err.message);
+    return false;
   });
 }
 
+/**
+ * @type {Map<string, Promise<boolean>>} "chat id:message id" -> the last append to it, so that appends don't overwrite each other
+ */
+const appendLocks = new Map();
+
+/**
+ * @param {TelegramBot.Message} message sent by sendMessage() with MarkdownV2
+ * @param {string} text MarkdownV2, appended as new lines
+ * @returns {Promise<boolean>} whether the message was edited; false if its text isn't known any more, it was sent
+ * with the fallback parse mode, the result would be too long, or Telegram refused the edit (e.g. it was deleted)
+ */
+function appendToMessage(message, text) {
+  const key = `${message.chat.id}:${message.message_id}`;
+  const appended = (appendLocks.get(key) || Promise.resolve(true)).then(async () => {
+    const sent = sentTexts.get(key);
+    if(!sent || sent.parseMode !== "MarkdownV2") return false;
+    const newText = sent.text + "\n" + text;
+    if(newText.length > 4000) return false;
+    try {
+      await bot.editMessageText(newText, {
+        chat_id: message.chat.id,
+        message_id: message.message_id,
+        parse_mode: "MarkdownV2",
+      });
+    } catch(err) {
+      verb(appendToMessage, "failed to edit message", key, "due to", err.message);
+      return false;
+    }
+    sent.text = newText;
+    return true;
+  });
+  appendLocks.set(key, appended);
+  appended.finally(() => {
+    if(appendLocks.get(key) === appended) appendLocks.delete(key);
+  });
+  return appended;
+}
 
 /**
  * @param {string | number} chatId
@@ -492,31 +819,27 @@ onText(/^\/add\s+(\S+)$/, async (msg, match) => {
 
   verb("trying to add", newPackageName);
 
-  if(packageStatus.filter(user => user.packages.some(existingPkg => existingPkg.name === newPackageName)).length) {
-    if(findUserIdByPackage(newPackageName) === msg.from.id) {
+  const ownerId = assignPackage(packageStatus, newPackageName, msg.from.id, msg.from.username, Date.now());
+  if(ownerId !== null) {
+    if(ownerId === msg.from.id) {
       await replyMessage(chatId, msgId, toSafeMd(`无需重复认领`));
     } else {
       await replyMessage(chatId, msgId, toSafeMd(`认领失败，这个 package 已被其他人认领`));
     }
+    if(shouldPingOwner(ownerId, msg.from.id, NOTIFY_ADD_CONFLICT)) {
+      const ownerLink = getMentionLink(ownerId, null, getAlias(ownerId));
+      const requesterLink = getMentionLink(msg.from.id, null, msg.from.first_name, msg.from.last_name, false);
+      await sendMessage(chatId, tgMessages.addConflict({ ownerLink, requesterLink, pkgname: newPackageName }), {
+        parse_mode: "MarkdownV2",
+        disable_notification: false,
+      });
+    }
     return;
   }
 
-  if(packageStatus.some(user => user.userid === msg.from.id)) {
-    packageStatus.find(user => user.userid === msg.from.id).packages.push({ 
-      name: newPackageName,
-      lastActive: Date.now(),
-    });
-  } else {
-    packageStatus.push({
-      userid: msg.from.id,
-      username: msg.from.username,
-      packages: [{
-        name: newPackageName,
-        lastActive: Date.now(),
-      }],
-    });
-  }
+  recordHistory(newPackageName, "add", { via: "bot", uid: msg.from.id });
   storePackageStatus();
+  storePackageHistory();
 
   const packageMark = packageMarks.filter(pkg => pkg.name === newPackageName)[0];
   if(packageMark && packageMark.marks.filter(mark => mark.name !== "failing").length) {
@@ -569,8 +892,10 @@ onText(/^\/(merge|drop)\s+(\S+)$/, async (msg, match) => {
  * @param {string} mergedPackageName
  * @param {number} userId
  * @param {(success: boolean, reason?: string) => any} callback
+ * @param {import("./utils").HistoryActorInterface} [actor] defaults to the bot user `userId`
+ * @param {string} [detail] why it is dropped, for the history
  */
-function _merge(mergedPackageName, userId, callback) {
+function _merge(mergedPackageName, userId, callback, actor = { via: "bot", uid: userId }, detail) {
   verb("trying to merge", mergedPackageName);
 
   if(!packageStatus.filter(user => user.packages.some(existingPkg => existingPkg.name === mergedPackageName)).length) {
@@ -586,7 +911,8 @@ function _merge(mergedPackageName, userId, callback) {
     }
     //@ts-ignore
     packageStatus.find(user => user.userid === userId).packages.remove(targetPackage);
-    storePackageStatus().then(() => callback(true)).catch(err => callback(false, String(err)));
+    recordHistory(mergedPackageName, "drop", actor, detail);
+    Promise.all([storePackageStatus(), storePackageHistory()]).then(() => callback(true)).catch(err => callback(false, String(err)));
     return;
   }
 
@@ -651,22 +977,10 @@ onText(MARK_REGEXP, async (msg, match) => {
 
   // cascading marks & unmarks
   if(markConfig.triggers.length > 0) {
-    const currMarks = getPackageMarkNamesByPkgname(pkg);
-    const shouldMark = [], shouldUnmark = [];
-    for(const trigger of markConfig.triggers) {
-      if(trigger.when !== "mark") {
-        continue;
-      }
-      if(trigger.op === "mark" && !currMarks.includes(trigger.name)) {
-        shouldMark.push(trigger.name);
-      } else if(trigger.op === "unmark" && currMarks.includes(trigger.name)) {
-        shouldUnmark.push(trigger.name);
-      }
-    }
+    const { shouldMark, shouldUnmark } = getTriggeredMarks(pkg, markConfig, "mark");
     if(shouldMark.length > 0) {
       verb(`triggered by this mark: should also mark`, shouldMark);
-      const prefix = wrapCode("(auto-mark)");
-      await sendMessage(chatId, prefix + toSafeMd(` ${pkg} 将被额外添加这些标记：${shouldMark.join(" ")}`), {
+      await sendMessage(chatId, tgMessages.cascadeMark({ pkgname: pkg, marks: shouldMark }), {
         parse_mode: "MarkdownV2",
       });
       const comments = [];
@@ -679,13 +993,12 @@ onText(MARK_REGEXP, async (msg, match) => {
     }
     if(shouldUnmark.length > 0) {
       verb(`triggered by this mark: should also unmark`, shouldUnmark);
-      const prefix = wrapCode("(auto-unmark)");
-      await sendMessage(chatId, prefix + toSafeMd(` ${pkg} 将被清除这些标记：${shouldUnmark.join(" ")}`), {
+      await sendMessage(chatId, tgMessages.cascadeUnmark({ pkgname: pkg, marks: shouldUnmark }), {
         parse_mode: "MarkdownV2",
       });
       // we don't care whether triggered unmarks are updated successfully or not,
       // so use a dummy callback
-      await _unmarkMultiple(pkg, shouldUnmark, () => {});
+      await _unmarkMultiple(pkg, shouldUnmark, () => {}, { via: "bot", uid: userId });
     }
   }
 
@@ -697,7 +1010,7 @@ onText(MARK_REGEXP, async (msg, match) => {
     if(success) {
       replyMessage(chatId, msgId, toSafeMd(`状态更新成功`));
       if(String(chatId) !== process.env["PLCT_CHAT_ID"]) {
-        sendMessage(process.env["PLCT_CHAT_ID"], toSafeMd(`${pkg} 已被标记为 ${mark}：${comment || "无注释"}`), {
+        sendMessage(getDestination("marks"), toSafeMd(`${pkg} 已被标记为 ${mark}：${comment || "无注释"}`), {
           parse_mode: "MarkdownV2"
         });
         sendMessage(chatId, toSafeMd(`deprecated: 不建议在 PLCT 群以外的地方更新包的状态`), {
@@ -714,6 +1027,28 @@ onText(MARK_REGEXP, async (msg, match) => {
   _mark(pkg, mark, comment, userId, mentionLink, markCallback);
 });
 
+/**
+ * @param {string} pkg
+ * @param {ReturnType<typeof getMarkConfig>} markConfig
+ * @param {"mark" | "unmark"} when
+ * @returns {{ shouldMark: string[]; shouldUnmark: string[]; }} cascading marks & unmarks that are not applied yet
+ */
+function getTriggeredMarks(pkg, markConfig, when) {
+  const currMarks = getPackageMarkNamesByPkgname(pkg);
+  const shouldMark = [], shouldUnmark = [];
+  for(const trigger of markConfig.triggers) {
+    if(trigger.when !== when) {
+      continue;
+    }
+    if(trigger.op === "mark" && !currMarks.includes(trigger.name)) {
+      shouldMark.push(trigger.name);
+    } else if(trigger.op === "unmark" && currMarks.includes(trigger.name)) {
+      shouldUnmark.push(trigger.name);
+    }
+  }
+  return { shouldMark, shouldUnmark };
+}
+
 /**
  * @param {string} pkg
  * @param {string} mark
@@ -721,31 +1056,35 @@ onText(MARK_REGEXP, async (msg, match) => {
  * @param {number} userId
  * @param {string} mentionLink
  * @param {(success: boolean, reason?: string) => any} callback
+ * @param {import("./utils").HistoryActorInterface} [actor] defaults to the bot user `userId`
  */
-async function _mark(pkg, mark, comment, userId, mentionLink, callback) {
+async function _mark(pkg, mark, comment, userId, mentionLink, callback, actor = { via: "bot", uid: userId }) {
   verb(_mark, pkg, mark, comment);
   if(packageMarks.filter(obj => obj.name === pkg).length > 0) {
     const target = packageMarks.filter(obj => obj.name === pkg)[0];
     if(!target.marks.some(markObj => markObj.name === mark)) {
-      target.marks.push({ name: mark, by: { url: mentionLink, uid: userId, alias: getAlias(userId) }, comment });
+      target.marks.push({ name: mark, by: { url: mentionLink, uid: userId, alias: getAlias(userId) }, comment, markedAt: Date.now() });
       target.marks.sort((a, b) => a.name > b.name ? 1 : a.name === b.name ? 0 : -1);
     } else {
       const markIndex = target.marks.findIndex(markObj => markObj.name === mark);
       target.marks[markIndex] = {
         name: mark,
         by: { url: mentionLink, uid: userId, alias: getAlias(userId) },
-        comment
+        comment,
+        // updating the comment doesn't make the mark any younger
+        markedAt: target.marks[markIndex].markedAt,
       };
     }
   } else {
     packageMarks.push({
       name: pkg,
-      marks: [ { name: mark, by: { url: mentionLink, uid: userId, alias: getAlias(userId) }, comment } ],
+      marks: [ { name: mark, by: { url: mentionLink, uid: userId, alias: getAlias(userId) }, comment, markedAt: Date.now() } ],
     });
     packageMarks.sort((pkg1, pkg2) => strcmp(pkg1.name, pkg2.name));
   }
+  recordHistory(pkg, "mark", actor, comment ? `${mark} ${comment}` : mark);
   try {
-    await storePackageMarks();
+    await Promise.all([storePackageMarks(), storePackageHistory()]);
   } catch(err) {
     callback(false, String(err));
     return false;
@@ -761,8 +1100,9 @@ async function _mark(pkg, mark, comment, userId, mentionLink, callback) {
  * @param {string[]} marks
  * @param {string[]} comments matching `marks` respectively
  * @param {(success: boolean, reason?: string) => any} callback will be invoked multiple times!
+ * @param {import("./utils").HistoryActorInterface} [actor] defaults to the bot user `userId`
  */
- async function _markMultiple(pkg, userId, mentionLink, marks, comments, callback) {
+ async function _markMultiple(pkg, userId, mentionLink, marks, comments, callback, actor) {
   let allSuccessful = true;
   if(!userId) {
     userId = BOT_ID;
@@ -782,7 +1122,7 @@ async function _mark(pkg, mark, comment, userId, mentionLink, callback) {
       comment = comment.trim();
     }
     // mark one-by-one to preserve order
-    if(false === await _mark(pkg, mark, comment, userId, mentionLink, callback)) {
+    if(false === await _mark(pkg, mark, comment, userId, mentionLink, callback, actor)) {
       allSuccessful = false;
     }
   }
@@ -834,7 +1174,7 @@ onText(/^\/unmark\s+(\S+)\s+(\S+)/, async (msg, match) => {
           parse_mode: "MarkdownV2"
         });
       }
-    });
+    }, { via: "bot", uid: userId });
     if(succ) {
       let respText = toSafeMd("已成功删除该包的 ");
       respText += targetMarks.map(mark => wrapCode(mark)).join(" ");
@@ -870,22 +1210,10 @@ onText(/^\/unmark\s+(\S+)\s+(\S+)/, async (msg, match) => {
 
   // cascading marks & unmarks
   if(markConfig.triggers.length > 0) {
-    const currMarks = getPackageMarkNamesByPkgname(pkg);
-    const shouldMark = [], shouldUnmark = [];
-    for(const trigger of markConfig.triggers) {
-      if(trigger.when !== "unmark") {
-        continue;
-      }
-      if(trigger.op === "mark" && !currMarks.includes(trigger.name)) {
-        shouldMark.push(trigger.name);
-      } else if(trigger.op === "unmark" && currMarks.includes(trigger.name)) {
-        shouldUnmark.push(trigger.name);
-      }
-    }
+    const { shouldMark, shouldUnmark } = getTriggeredMarks(pkg, markConfig, "unmark");
     if(shouldMark.length > 0) {
       verb(`triggered by this mark: should also mark`, shouldMark);
-      const prefix = wrapCode("(auto-mark)");
-      await sendMessage(chatId, prefix + toSafeMd(`${pkg} 将被额外添加这些标记：${shouldMark.join(" ")}`), {
+      await sendMessage(chatId, tgMessages.cascadeMark({ pkgname: pkg, marks: shouldMark }), {
         parse_mode: "MarkdownV2",
       });
       const comments = [];
@@ -898,13 +1226,12 @@ onText(/^\/unmark\s+(\S+)\s+(\S+)/, async (msg, match) => {
     }
     if(shouldUnmark.length > 0) {
       verb(`triggered by this mark: should also unmark`, shouldUnmark);
-      const prefix = wrapCode("(auto-unmark)");
-      await sendMessage(chatId, prefix + toSafeMd(`${pkg} 将被清除这些标记：${shouldUnmark.join(" ")}`), {
+      await sendMessage(chatId, tgMessages.cascadeUnmark({ pkgname: pkg, marks: shouldUnmark }), {
         parse_mode: "MarkdownV2",
       });
       // we don't care whether triggered unmarks are updated successfully or not,
       // so use a dummy callback
-      await _unmarkMultiple(pkg, shouldUnmark, () => {});
+      await _unmarkMultiple(pkg, shouldUnmark, () => {}, { via: "bot", uid: userId });
     }
   }
 
@@ -918,7 +1245,7 @@ onText(/^\/unmark\s+(\S+)\s+(\S+)/, async (msg, match) => {
         parse_mode: "MarkdownV2"
       });
       if(String(chatId) !== process.env["PLCT_CHAT_ID"]) {
-        sendMessage(process.env["PLCT_CHAT_ID"], toSafeMd(`${pkg} 不再被标记为 ${mark}`), {
+        sendMessage(getDestination("marks"), toSafeMd(`${pkg} 不再被标记为 ${mark}`), {
           parse_mode: "MarkdownV2"
         });
         sendMessage(chatId, toSafeMd(`deprecated: 不建议在 PLCT 群以外的地方更新包的状态`), {
@@ -932,19 +1259,20 @@ onText(/^\/unmark\s+(\S+)\s+(\S+)/, async (msg, match) => {
     }
   }
 
-  _unmark(pkg, mark, unmarkCallback);
+  _unmark(pkg, mark, unmarkCallback, { via: "bot", uid: userId });
 });
 
 /**
  * @param {string} pkg
  * @param {string[]} marks
  * @param {(success: boolean, reason?: string) => any} callback will be invoked multiple times!
+ * @param {import("./utils").HistoryActorInterface} actor
  */
-async function _unmarkMultiple(pkg, marks, callback) {
+async function _unmarkMultiple(pkg, marks, callback, actor) {
   let allSuccessful = true;
   for(const mark of marks) {
     // unmark one-by-one to preserve order
-    if(false === await _unmark(pkg, mark, callback)) {
+    if(false === await _unmark(pkg, mark, callback, actor)) {
       allSuccessful = false;
     }
   }
@@ -955,17 +1283,19 @@ async function _unmarkMultiple(pkg, marks, callback) {
  * @param {string} pkg
  * @param {string} mark
  * @param {(success: boolean, reason?: string) => any} callback
+ * @param {import("./utils").HistoryActorInterface} actor
  * @description 取消某个包的某个标记。不会等待 callback 执行完毕才返回。
  */
-async function _unmark(pkg, mark, callback) {
+async function _unmark(pkg, mark, callback, actor) {
   if(packageMarks.filter(obj => obj.name === pkg).length > 0) {
     const target = packageMarks.filter(obj => obj.name === pkg)[0];
     if(target.marks.some(markObj => markObj.name === mark)) {
       target.marks = target.marks.filter(markObj => markObj.name !== mark);
       target.marks.sort((a, b) => a.name > b.name ? 1 : a.name === b.name ? 0 : -1);
       verb(_unmark, pkg, mark);
+      recordHistory(pkg, "unmark", actor, mark);
       try {
-        await storePackageMarks();
+        await Promise.all([storePackageMarks(), storePackageHistory()]);
       } catch {
         callback(false, "未能写入数据库");
         return false;
@@ -1010,6 +1340,24 @@ onText(/^\/status@?/, async (msg) => {
   await replyMessage(chatId, msgId, statusStr, { parse_mode: "MarkdownV2" });
 });
 
+onText(/^\/mine(?:@[\S]+?)?$/, async (msg) => {
+  const chatId = msg.chat.id;
+  const msgId = msg.message_id;
+
+  verb("trying to show packages of", msg.from.id);
+
+  const user = packageStatus.find(user => user.userid === msg.from.id);
+  if(!user || !user.packages.length) {
+    await replyMessage(chatId, msgId, toSafeMd("你还没有认领任何 package"), { parse_mode: "MarkdownV2" });
+    return;
+  }
+  const statusStr = user.packages.map(pkg => {
+    const marks = getPackageMarksByPkgname(pkg.name);
+    return "`" + toSafeCode(pkg.name) + "`" + (marks.length ? " " + marksToStringArr(marks).join(" ") : "");
+  }).join("\n");
+  await replyMessage(chatId, msgId, statusStr, { parse_mode: "MarkdownV2" });
+});
+
 onText(/^\/more(?:@[\S]+?)?\s+([\S]+)$/, async (msg, match) => {
   const chatId = msg.chat.id;
   const msgId = msg.message_id;
@@ -1092,6 +1440,19 @@ onText(/^\/reloadalias(?:@[\S]+?)?$/, async (msg) => {
   await sendMessage(chatId, "Alias reloaded.");
 });
 
+onText(/^\/reloadtokens(?:@[\S]+?)?$/, async (msg) => {
+  const chatId = msg.chat.id;
+  if(msg.from.id !== ADMIN_ID) {
+    return;
+  }
+  try {
+    const tokens = await loadApiTokens();
+    await sendMessage(chatId, `Tokens reloaded: ${tokens.length} tokens.`);
+  } catch(e) {
+    await sendMessage(chatId, `Failed to reload tokens: ${e.message}`);
+  }
+});
+
 bot.on("message", (msg) => {
   const text = msg.text;
   if(text && text.startsWith("/")) {
@@ -1100,256 +1461,1920 @@ bot.on("message", (msg) => {
 });
 
 /**
- * @param {http.IncomingMessage} req 
- * @param {http.ServerResponse} res 
+ * @param {http.IncomingMessage} req
+ * @param {"add" | "mark" | "delete" | "reassign" | "notify" | "audit"} scope
+ * @param {any} [bodyToken] token in the JSON body of POST routes
+ * @description auth check shared by every mutating route and /history. An X-Signature header takes precedence
+ * over tokens, see getProvidedToken() for where tokens are taken from.
+ * Signed requests of named tokens carry X-Token-Name; they are keyed with the token's hash.
  */
-async function routePkgHandler(req, res) {
-  verb(routePkgHandler);
+function isAuthorized(req, scope, bodyToken) {
   const url = new URL(req.url, `http://${req.headers.host}`);
-  if(!url.searchParams.has("mark")) {
-    res.writeHead(200, { 'Content-Type': 'application/json' });
-    const data = {
-      workList: stripPackageStatus(packageStatus),
-      markList: stripPackageMarks(packageMarks),
-    };
-    res.end(JSON.stringify(data));
-  } else {
-    const markName = url.searchParams.get("mark");
-    const packages = packageMarks.filter(pkg => pkg.marks.some(mark => mark.name === markName)).map(pkg => pkg.name);
-    res.writeHead(200, { 'Content-Type': 'application/json' });
-    res.end(JSON.stringify(packages));
+  if(req.headers["x-signature"] !== undefined) {
+    const tokenName = req.headers["x-token-name"];
+    if(tokenName === undefined) {
+      return verifySignature(req.headers["x-signature"], req.headers["x-timestamp"], url.pathname,
+        HTTP_API_TOKEN, HTTP_SIGNATURE_MAX_AGE);
+    }
+    const apiToken = findApiTokenByName(tokenName);
+    return apiToken !== null && apiToken.scopes.includes(scope) &&
+      verifySignature(req.headers["x-signature"], req.headers["x-timestamp"], url.pathname,
+        apiToken.hash, HTTP_SIGNATURE_MAX_AGE);
   }
+  const provided = getProvidedToken(req, bodyToken);
+  if(verifyToken(provided, HTTP_API_TOKEN)) {
+    return true;
+  }
+  const apiToken = findApiToken(provided);
+  return apiToken !== null && apiToken.scopes.includes(scope);
 }
 
 /**
- * @param {http.IncomingMessage} req 
- * @param {http.ServerResponse} res 
+ * @param {http.IncomingMessage} req an authorized request
+ * @param {any} [bodyToken]
+ * @returns {string} name of the token the request was authorized with, "" for PLCT_HTTP_API_TOKEN
  */
-async function routeDeleteHandler(req, res) {
-  const url = new URL(req.url, `http://${req.headers.host}`);
-  const args = url.pathname.slice(1).split("/");
-  verb(routeDeleteHandler, args);
-
-  const apiToken = url.searchParams.get("token");
-  if(apiToken !== HTTP_API_TOKEN) {
-    res.writeHead(403, { 'Content-Type': 'text/plain' });
-    res.end("Forbidden");
-    return;
+function getTokenName(req, bodyToken) {
+  if(req.headers["x-signature"] !== undefined) {
+    const tokenName = req.headers["x-token-name"];
+    return typeof tokenName === "string" ? tokenName : "";
   }
-  if(args.length != 3) {
-    res.writeHead(400, { 'Content-Type': 'text/plain' });
-    res.end("Bad Request");
-    return;
+  const apiToken = findApiToken(getProvidedToken(req, bodyToken));
+  return apiToken === null ? "" : apiToken.name;
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {any} [bodyToken]
+ * @returns {any} `Authorization: Bearer` first, then the body token, then the query string token while
+ * HTTP_ALLOW_QUERY_TOKEN is on; undefined if there is none
+ */
+function getProvidedToken(req, bodyToken) {
+  const authorization = req.headers["authorization"];
+  if(typeof authorization === "string" && /^Bearer /i.test(authorization)) {
+    return authorization.slice("Bearer ".length).trim();
   }
-  const pkgname = decodeURIComponent(args[1]), status = args[2];
-  if(status !== "ftbfs" && status !== "leaf") {
-    res.writeHead(400, { 'Content-Type': 'text/plain' });
-    res.end("Bad Request");
-    return;
+  if(bodyToken !== undefined) {
+    return bodyToken;
   }
-  res.writeHead(200, { 'Content-Type': 'text/plain' });
-
-  const userId = localUtils.findUserIdByPackage(pkgname);
-  verb(routeDeleteHandler, "userId is", userId);
-  if(userId === null) {
-    res.write("package not found;");
-  } else {
-    const alias = getAlias(userId);
-    const link = getMentionLink(userId, null, alias);
-    const msgTypeStr = wrapCode("(auto-merge)");
-    sendMessage(CHAT_ID, msgTypeStr + " ping " + link + toSafeMd(`: ${pkgname} 已出包`), {
-      parse_mode: "MarkdownV2",
-    }, true);
-
-    _merge(pkgname, userId, (success, reason) => {
-      if(success) return;
-      sendMessage(CHAT_ID, msgTypeStr + toSafeMd(` failed: ${reason}`), {
-        parse_mode: "MarkdownV2",
-      }, true);
-    });
+  if(!HTTP_ALLOW_QUERY_TOKEN) {
+    return undefined;
   }
+  const queryToken = new URL(req.url, `http://${req.headers.host}`).searchParams.get("token");
+  return queryToken === null ? undefined : queryToken;
+}
 
-  ;(async function fencedAtomicOps() {
-  // 自动出包后，首先把这个包的特定 mark 清掉
-  const targetMarks = ["outdated", "stuck", "ready", "outdated_dep", "missing_dep", "unknown", "ignore", "failing"];
-  await _unmarkMultiple(pkgname, targetMarks, (success, reason) => {
-    if(!success) {
-      return;
-    }
-    // for success === true, `reason` is the name of the modified mark
-    const mark = reason;
-    const msgTypeStr = wrapCode("(auto-unmark)");
-    // 需要这个部分在后面的 Ping + defer msg 之前输出，所以这里并不 defer
-    sendMessage(CHAT_ID, msgTypeStr + toSafeMd(` ${pkgname} 已出包，不再被标记为 ${mark}`), {
-      parse_mode: "MarkdownV2",
-    }, true);
-  });
-  // 到这里，sendMessage 也跑完了（pushQueue 完成）
-
-  // 之后清掉别的包有关这个包的特定 mark
-  const refMarks = ["outdated_dep", "missing_dep"];
-  const targetPackages = findPackageMarksByMarkNamesAndComment(refMarks, `[${pkgname}]`);
-  verb(routeDeleteHandler, "other pkgs with marks pointing to", pkgname, ":", targetPackages);
-  for(const pkg of targetPackages) {
-    // 效果上需要先 Ping 后输出内容，但遍历完才能知道需要 Ping 谁，所以把输出手动 defer 到最后
-    const deferKey = crypto.randomBytes(16).toString("hex");
-    /**
-     * @type {Set<string>}
-     */
-    const mentionLinkSet = new Set();
-    for(const mark of pkg.marks.slice()) {
-      if(!refMarks.includes(mark.name)) continue;
-      let mentionLink = BOT_MENTION_LINK;
-      if(mark.by) {
-        // we don't use mark.by.url here, because it is based on tg name but we want alias
-        mentionLink = getMentionLink(mark.by.uid, null, getAlias(mark.by.uid));
-        mentionLinkSet.add(mentionLink);
-      }
-      const uid = mark.by ? mark.by.uid : BOT_ID;
-      if(mark.comment.toLowerCase() === `[${pkgname}]`.toLowerCase()) {
-        verb(routeDeleteHandler, "(auto) full match:", pkgname, "is", pkg.name, mark.name, mark.comment);
-        await _unmark(pkg.name, mark.name, (success, _) => {
-          if(!success) {
-            verb(routeDeleteHandler, "failed to unmark", pkg.name, mark.name, _);
-            return;
-          }
-          const msgTypeStr = wrapCode("(auto-unmark)");
-          // defer 输出
-          defer.add(deferKey, () => {
-            sendMessage(CHAT_ID, msgTypeStr + toSafeMd(` ${pkg.name} 因 ${pkgname} 出包，不再被标记为 ${mark.name}`), {
-              parse_mode: "MarkdownV2",
-            }, true);
-          });
-        });
-      } else {
-        verb(routeDeleteHandler, "(auto) partial match:", pkgname, "in", pkg.name, mark.name, mark.comment);
-        const safePkgname = escapeRegExp(pkgname);
-        const comment = mark.comment.replace(new RegExp("\\[" + safePkgname + "\\]", "i"), "").trim();
-        verb(routeDeleteHandler, "built regexp name:", safePkgname, "-> new comment:", comment);
-        await _mark(pkg.name, mark.name, comment, uid, mentionLink, (success, _) => {
-          if(!success) {
-            verb(routeDeleteHandler, "failed to mark", pkg.name, mark.name, mark.comment, _);
-            return;
-          }
-          const msgTypeStr = wrapCode("(auto-mark)");
-          // defer 输出
-          defer.add(deferKey, () => {
-            sendMessage(CHAT_ID, msgTypeStr + toSafeMd(` [${pkgname}] 已从 ${pkg.name} 的 ${mark.name} 状态内移除。`), {
-              parse_mode: "MarkdownV2",
-            }, true);
-          });
-        });
-      }
-    }
-    if(mentionLinkSet.size > 0) {
-      let pingStr = wrapCode("(auto-cc)") + " ping";
-      Array.from(mentionLinkSet).forEach(link => {
-        pingStr += " " + link;
-      });
-      pingStr += toSafeMd(":");
-      // 先发送 ping 消息
-      sendMessage(CHAT_ID, pingStr, { parse_mode: "MarkdownV2" }, true);
-      // 再发送此前被 defer 的输出
-      defer.resolve(deferKey);
-    }
+/**
+ * @param {string} segment the pkgname path segment
+ * @returns {string} the pkgname, throws a 400 HttpError if it is invalid
+ */
+function decodePkgname(segment) {
+  const pkgname = decodePkgnameSegment(segment);
+  if(pkgname === null) {
+    throw new HttpError(400, `invalid pkgname ${JSON.stringify(segment.slice(0, 64))}`);
   }
-  })();  // invoke fencedAtomicOps()
-  res.end("success");
+  return pkgname;
 }
 
 /**
- * @param {http.IncomingMessage} req
- * @param {http.ServerResponse} res
+ * @param {URL} url
+ * @returns {string} the chat of `?arch=`, CHAT_ID without it; throws a 400 HttpError for unknown archs
  */
-async function routeAddHandler(req, res) {
-  const url = new URL(req.url, `http://${req.headers.host}`);
-  const args = url.pathname.slice(1).split("/");
-  verb(routeAddHandler, args);
-
-  const apiToken = url.searchParams.get("token");
-  if(apiToken !== HTTP_API_TOKEN) {
-    res.writeHead(403, { 'Content-Type': 'text/plain' });
-    res.end("Forbidden");
-    return;
+function getChatIdByArch(url) {
+  const arch = url.searchParams.get("arch");
+  if(arch === null) {
+    return CHAT_ID;
   }
-  if(args.length != 3) {
-    res.writeHead(400, { 'Content-Type': 'text/plain' });
-    res.end("Bad Request");
-    return;
+  if(!ARCH_CHAT_IDS.has(arch)) {
+    throw new HttpError(400, `unknown arch ${arch}; available: ${Array.from(ARCH_CHAT_IDS.keys()).join(", ")}`);
   }
-  const pkgname = decodeURIComponent(args[1]), status = args[2];
-  if(status !== "ftbfs") {
-    res.writeHead(400, { 'Content-Type': 'text/plain' });
-    res.end("Bad Request");
-    return;
+  return ARCH_CHAT_IDS.get(arch);
+}
+
+/**
+ * @param {Purpose} purpose
+ * @param {string} [chatId] the chat of the request, see getChatIdByArch()
+ * @returns {Destination} the destination configured for `purpose`, `chatId` itself when there is none.
+ * The chats of other archs get everything but what goes to the admin chat
+ */
+function getDestination(purpose, chatId = CHAT_ID) {
+  if(chatId !== CHAT_ID && purpose !== "admin") {
+    return { chatId, threadId: null };
   }
-  res.writeHead(200, { 'Content-Type': 'text/plain' });
+  return CHAT_DESTINATIONS.get(purpose) || { chatId, threadId: null };
+}
 
-  const userId = localUtils.findUserIdByPackage(pkgname);
+/**
+ * @param {string} pkgname
+ * @param {Destination} destination
+ * @returns {{ reply_to_message_id?: number; allow_sending_without_reply?: boolean; }} options replying to
+ * the last ping about `pkgname` there, if any. Telegram sends the message without the reply if that ping was deleted
+ */
+function getReplyOptions(pkgname, destination) {
+  const messageId = findPackageMessageId(pkgname, destination.chatId, destination.threadId);
+  return messageId === null ? {} : { reply_to_message_id: messageId, allow_sending_without_reply: true };
+}
+
+/**
+ * @param {string} pkgname
+ * @param {Destination} destination
+ * @param {Promise<TelegramBot.Message>} sent the ping about `pkgname`, later notifications about it reply to it
+ * @returns {Promise<TelegramBot.Message>} `sent`
+ */
+function rememberPing(pkgname, destination, sent) {
+  sent.then(message => {
+    setPackageMessageId(pkgname, destination.chatId, destination.threadId, message.message_id);
+    return storePackageMessages();
+  }).catch(err => verb(rememberPing, "not remembering the ping about", pkgname, err.message));
+  return sent;
+}
+
+const HTTP_MAX_BODY_SIZE = 1024 * 1024;
+
+/**
+ * @param {http.IncomingMessage} req
+ * @returns {Promise<any>} rejects if the body is too large or not valid JSON
+ */
+function readJsonBody(req) {
+  return new Promise((resolve, reject) => {
+    let body = "";
+    req.setEncoding("utf8");
+    req.on("data", (chunk) => {
+      body += chunk;
+      if(body.length > HTTP_MAX_BODY_SIZE) {
+        reject(new Error("request body too large"));
+        req.destroy();
+      }
+    });
+    req.on("end", () => {
+      try {
+        resolve(JSON.parse(body));
+      } catch(err) {
+        reject(err);
+      }
+    });
+    req.on("error", reject);
+  });
+}
+
+const PKG_DEFAULT_PAGE_SIZE = 50;
+const PKG_MAX_PAGE_SIZE = 500;
+
+/**
+ * @param {URLSearchParams} searchParams
+ * @param {string} name
+ * @param {number} defaultValue
+ * @returns {number} `NaN` if the param is present but not a non-negative integer
+ */
+function getIntSearchParam(searchParams, name, defaultValue) {
+  if(!searchParams.has(name)) {
+    return defaultValue;
+  }
+  const value = searchParams.get(name);
+  if(!/^\d+$/.test(value)) {
+    return NaN;
+  }
+  return parseInt(value, 10);
+}
+
+/**
+ * @param {(string | number)[]} fields
+ */
+function toCsvRow(fields) {
+  return fields.map(field => {
+    const str = String(field);
+    return /[",\r\n]/.test(str) ? `"${str.replace(/"/g, '""')}"` : str;
+  }).join(",") + "\r\n";
+}
+
+/**
+ * @param {http.IncomingMessage} req 
+ * @param {http.ServerResponse} res 
+ * @description every assigned or marked package in one row, written row by row
+ */
+async function routePkgCsvHandler(req, res) {
+  verb(routePkgCsvHandler);
+  res.writeHead(200, {
+    'Content-Type': 'text/csv; charset=utf-8',
+    'Content-Disposition': 'attachment; filename="pkg-status.csv"',
+  });
+  // new columns go to the end, for scripts that read them by position
+  res.write(toCsvRow(["pkgname", "alias", "tg_uid", "marks", "assigned_at"]));
+  /**
+   * @type {Set<string>}
+   */
+  const written = new Set();
+  for(const user of packageStatus) {
+    for(const pkg of user.packages) {
+      written.add(pkg.name);
+      const marks = getPackageMarkNamesByPkgname(pkg.name);
+      res.write(toCsvRow([pkg.name, getAlias(user.userid), user.userid, marks.join(";"), new Date(pkg.assignedAt).toISOString()]));
+    }
+  }
+  // packages that are marked but not assigned to anyone
+  for(const pkg of packageMarks) {
+    if(written.has(pkg.name) || pkg.marks.length === 0) continue;
+    res.write(toCsvRow([pkg.name, "", "", pkg.marks.map(mark => mark.name).join(";"), ""]));
+  }
+  res.end();
+}
+
+/**
+ * @param {http.IncomingMessage} req 
+ * @param {http.ServerResponse} res 
+ */
+async function routePkgHandler(req, res) {
+  verb(routePkgHandler);
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const format = url.searchParams.get("format");
+  if(format === "csv") {
+    await routePkgCsvHandler(req, res);
+    return;
+  }
+  if(format !== null && format !== "json") {
+    throw new HttpError(400);
+  }
+  const etag = getPkgEtag(url);
+  if(isNotModified(req, etag)) {
+    res.writeHead(304, { 'ETag': etag });
+    res.end();
+    return;
+  }
+  // ?fresh=true skips the cache, for debugging
+  let body = url.searchParams.get("fresh") === "true" ? undefined : pkgResponseCache.get(etag);
+  if(body === undefined) {
+    verb(routePkgHandler, "cache miss", etag);
+    body = JSON.stringify(buildPkgData(url));
+    cachePkgResponse(etag, body);
+  }
+  res.writeHead(200, { 'Content-Type': 'application/json', 'ETag': etag });
+  res.end(body);
+}
+
+/**
+ * @param {URL} url
+ * @description the JSON of /pkg, throws HttpError for invalid params
+ */
+function buildPkgData(url) {
+  if(!url.searchParams.has("mark")) {
+    let filteredStatus = packageStatus;
+    let filteredMarks = packageMarks;
+    if(url.searchParams.has("packager") || url.searchParams.has("alias")) {
+      const packagerUid = url.searchParams.get("packager");
+      const alias = url.searchParams.get("alias");
+      filteredStatus = packageStatus.filter(user => {
+        return (packagerUid === null || String(user.userid) === packagerUid) &&
+          (alias === null || getAlias(user.userid) === alias);
+      });
+      const pkgnames = filteredStatus.flatMap(user => user.packages.map(pkg => pkg.name));
+      filteredMarks = packageMarks.filter(pkg => pkgnames.includes(pkg.name));
+    }
+    const sort = url.searchParams.get("sort") || "oldest";
+    if(sort !== "oldest" && sort !== "newest") {
+      throw new HttpError(400, `unknown sort ${sort}; available: oldest, newest`);
+    }
+    let workList = stripPackageStatus(filteredStatus, sort);
+    let markList = stripPackageMarks(filteredMarks);
+    /**
+     * @type {{
+        workList: typeof workList;
+        markList: typeof markList;
+        totalWork?: number;
+        totalMarks?: number;
+      }}
+     */
+    const data = { workList, markList };
+
+    // without any of these params, the response stays the same as before
+    if(["offset", "limit", "page", "per_page", "marked"].some(key => url.searchParams.has(key))) {
+      let offset = getIntSearchParam(url.searchParams, "offset", 0);
+      let limit = getIntSearchParam(url.searchParams, "limit", PKG_MAX_PAGE_SIZE);
+      if(url.searchParams.has("page") || url.searchParams.has("per_page")) {
+        // page-based params take precedence over offset & limit, page starts from 1
+        const page = getIntSearchParam(url.searchParams, "page", 1);
+        limit = getIntSearchParam(url.searchParams, "per_page", PKG_DEFAULT_PAGE_SIZE);
+        offset = (page - 1) * limit;
+        if(page < 1) {
+          offset = NaN;
+        }
+      }
+      if(isNaN(offset) || isNaN(limit)) {
+        throw new HttpError(400);
+      }
+      limit = Math.min(limit, PKG_MAX_PAGE_SIZE);
+      if(url.searchParams.has("marked")) {
+        const markName = url.searchParams.get("marked");
+        markList = markList.filter(pkg => pkg.marks.some(mark => mark.name === markName));
+      }
+      data.totalWork = workList.length;
+      data.totalMarks = markList.length;
+      data.workList = workList.slice(offset, offset + limit);
+      data.markList = markList.slice(offset, offset + limit);
+    }
+    return data;
+  } else {
+    // ?mark=a,b and ?mark=a&mark=b both match packages with any of the marks
+    const markNames = url.searchParams.getAll("mark").flatMap(value => value.split(","));
+    const availableMarks = getAvailableMarks();
+    const unknownMarks = markNames.filter(markName => !availableMarks.includes(markName));
+    if(unknownMarks.length > 0) {
+      throw new HttpError(400, `unknown mark ${unknownMarks.join(", ")}; available: ${availableMarks.join(", ")}`);
+    }
+    return packageMarks.filter(pkg => pkg.marks.some(mark => markNames.includes(mark.name))).map(pkg => pkg.name);
+  }
+}
+
+// tells the data versions of different runs apart, since getDataVersion() starts from 0 again
+const DATA_EPOCH = Date.now().toString(36);
+
+/**
+ * @param {URL} url
+ * @description changes with the data version, the day (for daysAssigned) and the query,
+ * so a 304 needs neither the data nor its serialization
+ */
+function getPkgEtag(url) {
+  const searchParams = new URLSearchParams(url.searchParams);
+  // neither changes the response
+  searchParams.delete("token");
+  searchParams.delete("fresh");
+  searchParams.sort();
+  const queryHash = crypto.createHash("sha1").update(searchParams.toString(), "utf8").digest("base64url").slice(0, 10);
+  return `W/"${DATA_EPOCH}.${getDataVersion()}.${getDayNumber(Date.now())}.${queryHash}"`;
+}
+
+// different queries have different ETags, keep the bodies of a few of them
+const PKG_CACHE_MAX_ENTRIES = 32;
+
+/**
+ * @type {Map<string, string>} serialized /pkg responses by ETag, only of the current data version
+ */
+const pkgResponseCache = new Map();
+let pkgResponseCacheVersion = -1;
+
+/**
+ * @param {string} etag
+ * @param {string} body
+ */
+function cachePkgResponse(etag, body) {
+  if(pkgResponseCacheVersion !== getDataVersion()) {
+    // bodies of older versions will never be asked for again
+    pkgResponseCache.clear();
+    pkgResponseCacheVersion = getDataVersion();
+  }
+  if(pkgResponseCache.size >= PKG_CACHE_MAX_ENTRIES) {
+    // Map keeps insertion order, so this is the oldest
+    pkgResponseCache.delete(pkgResponseCache.keys().next().value);
+  }
+  pkgResponseCache.set(etag, body);
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {string} etag
+ */
+function isNotModified(req, etag) {
+  const ifNoneMatch = req.headers["if-none-match"];
+  return typeof ifNoneMatch === "string" && ifNoneMatch.split(",").some(tag => tag.trim() === etag || tag.trim() === "*");
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ */
+async function routePkgDetailHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const args = url.pathname.slice(1).split("/");
+  verb(routePkgDetailHandler, args);
+
+  if(args.length != 2) {
+    throw new HttpError(400);
+  }
+  const pkgname = decodePkgname(args[1]);
+  const user = packageStatus.find(user => user.packages.some(pkg => pkg.name === pkgname));
+  const pkgMarks = stripPackageMarks(packageMarks.filter(pkg => pkg.name === pkgname && pkg.marks.length > 0));
+  if(!user && pkgMarks.length === 0) {
+    throw new HttpError(404);
+  }
+  const data = {
+    name: pkgname,
+    packager: user ? {
+      alias: getAlias(user.userid),
+      tgUid: user.userid,
+      // set when the package was claimed
+      lastActive: user.packages.find(pkg => pkg.name === pkgname).lastActive,
+      assignedAt: new Date(user.packages.find(pkg => pkg.name === pkgname).assignedAt).toISOString(),
+    } : null,
+    marks: pkgMarks.length > 0 ? pkgMarks[0].marks : [],
+  };
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(data));
+}
+
+const SERVICE_VERSION = require("../package.json").version;
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ * @description GET / for clients not asking for HTML, enough to tell a deploy is up and has its data
+ */
+async function routeRootHandler(req, res) {
+  const data = {
+    service: "archrv-pkg-bot",
+    assignments: packageStatus.reduce((sum, user) => sum + user.packages.length, 0),
+    version: SERVICE_VERSION,
+  };
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(data));
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ * @description the working list and marks as an HTML page, grouped by packager
+ */
+async function routeDashboardHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const packager = url.searchParams.get("packager") || null;
+  verb(routeDashboardHandler, packager);
+
+  let users = packageStatus.filter(user => user.packages.length > 0);
+  if(packager !== null) {
+    const userId = findUserIdByPackager(packager);
+    if(userId === null) {
+      throw new HttpError(404, `unknown packager ${packager}`);
+    }
+    users = packageStatus.filter(user => user.userid === userId);
+  }
+  const strippedMarks = stripPackageMarks(packageMarks.filter(pkg => pkg.marks.length > 0));
+  /**
+   * @param {string} pkgname
+   */
+  const getMarks = (pkgname) => {
+    const pkgMarks = strippedMarks.find(pkg => pkg.name === pkgname);
+    return (pkgMarks ? pkgMarks.marks : []).map(mark => ({
+      name: mark.name,
+      desc: localUtils.MARK2STR[mark.name] || "",
+      comment: mark.comment,
+      by: mark.by.alias,
+    }));
+  };
+  const assigned = new Set(packageStatus.flatMap(user => user.packages.map(pkg => pkg.name)));
+  const html = renderDashboard({
+    packagers: users.map(user => ({
+      alias: getAlias(user.userid),
+      tgUid: user.userid,
+      packages: user.packages.map(pkg => ({ name: pkg.name, marks: getMarks(pkg.name) })),
+    })),
+    unassigned: packager !== null ? null : strippedMarks.filter(pkg => !assigned.has(pkg.name))
+      .map(pkg => ({ name: pkg.name, marks: getMarks(pkg.name) })),
+    packager,
+  });
+  res.writeHead(200, { 'Content-Type': 'text/html; charset=utf-8' });
+  res.end(html);
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ */
+async function routeMarksHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const args = url.pathname.slice(1).split("/");
+  verb(routeMarksHandler, args);
+
+  if(args.length != 2 || args[1] === "") {
+    throw new HttpError(400);
+  }
+  const pkgname = decodePkgname(args[1]);
+  // unlike /pkg/{pkgname}, a package without marks is not an error here
+  const pkgMarks = stripPackageMarks(packageMarks.filter(pkg => pkg.name === pkgname));
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(pkgMarks.length > 0 ? pkgMarks[0].marks : []));
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ * @description GET /assign/check/{pkgname}, whether someone has claimed the package, without changing anything
+ */
+async function routeAssignCheckHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const args = url.pathname.slice(1).split("/");
+  verb(routeAssignCheckHandler, args);
+
+  if(args.length != 3 || args[2] === "") {
+    throw new HttpError(400);
+  }
+  const pkgname = decodePkgname(args[2]);
+  const userId = findUserIdByPackage(pkgname);
+  const data = userId === null ? { assigned: false } : { assigned: true, alias: getAlias(userId), tgUid: userId };
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(data));
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ * @description packages whose `mark` is older than `days` days, with only that mark in their `marks`
+ */
+async function routeStaleMarksHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const mark = url.searchParams.get("mark");
+  const days = getIntSearchParam(url.searchParams, "days", 14);
+  verb(routeStaleMarksHandler, mark, days);
+
+  if(mark === null || isNaN(days)) {
+    throw new HttpError(400);
+  }
+  if(!getAvailableMarks().includes(mark)) {
+    throw new HttpError(400, `unknown mark ${mark}; available: ${getAvailableMarks().join(", ")}`);
+  }
+  const cutoff = Date.now() - days * 24 * 3600 * 1e3;
+  const stalePackages = stripPackageMarks(findStalePackageMarks(mark, cutoff)).map(pkg => ({
+    name: pkg.name,
+    marks: pkg.marks.filter(markObj => markObj.name === mark),
+  }));
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(stalePackages));
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ */
+async function routePackagerHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const args = url.pathname.slice(1).split("/");
+  verb(routePackagerHandler, args);
+
+  if(args.length != 2 || args[1] === "") {
+    throw new HttpError(400);
+  }
+  // 路径里既可以是 tg uid，也可以是 alias
+  let packager;
+  try {
+    packager = decodeURIComponent(args[1]);
+  } catch(e) {
+    throw new HttpError(400, `invalid packager ${JSON.stringify(args[1].slice(0, 64))}`);
+  }
+  const userId = findUserIdByPackager(packager);
+  if(userId === null) {
+    throw new HttpError(404);
+  }
+  const user = packageStatus.find(user => user.userid === userId);
+  const strippedMarks = stripPackageMarks(packageMarks);
+  const data = {
+    alias: getAlias(userId),
+    packages: (user ? user.packages : []).map(pkg => {
+      const pkgMarks = strippedMarks.find(markPkg => markPkg.name === pkg.name);
+      return {
+        name: pkg.name,
+        marks: pkgMarks ? pkgMarks.marks : [],
+      };
+    }),
+  };
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(data));
+}
+
+/**
+ * @param {http.IncomingMessage} req 
+ * @param {http.ServerResponse} res 
+ */
+async function routeHistoryHandler(req, res) {
+  verb(routeHistoryHandler);
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  if(!isAuthorized(req, "audit")) {
+    throw new HttpError(403);
+  }
+  let limit = getIntSearchParam(url.searchParams, "limit", PKG_DEFAULT_PAGE_SIZE);
+  // UTC epoch seconds, like `timestamp` of the entries
+  const since = getIntSearchParam(url.searchParams, "since", 0);
+  const until = getIntSearchParam(url.searchParams, "until", Infinity);
+  if(Number.isNaN(limit) || limit < 0 || Number.isNaN(since) || Number.isNaN(until)) {
+    throw new HttpError(400);
+  }
+  limit = Math.min(limit, PKG_MAX_PAGE_SIZE);
+  // `until` covers the entries within its second as well
+  const history = getPackageHistory(url.searchParams.get("pkgname"), limit, since * 1000, until * 1000 + 999)
+    .map(stripHistoryEntry);
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify({ history }));
+}
+
+/**
+ * @param {import("./utils").HistoryInterface} entry
+ * @description the output format of /history and /events
+ */
+function stripHistoryEntry(entry) {
+  return {
+    pkgname: entry.pkgname,
+    action: entry.action,
+    actor: {
+      via: entry.actor.via,
+      tgUid: entry.actor.uid,
+      alias: entry.actor.uid === null ? null : getAlias(entry.actor.uid),
+    },
+    detail: entry.detail,
+    time: entry.time,
+    // UTC epoch seconds
+    timestamp: Math.floor(entry.time / 1000),
+  };
+}
+
+// a client that lets this much pile up is too slow (or gone), and is dropped instead of buffering forever
+const EVENTS_MAX_BUFFERED = 1024 * 1024;
+/**
+ * @type {Set<http.ServerResponse>}
+ */
+const eventStreams = new Set();
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ * @description server-sent events of every change of assignments and marks, in the format of /history
+ */
+async function routeEventsHandler(req, res) {
+  verb(routeEventsHandler, "client connected, total", eventStreams.size + 1);
+  res.writeHead(200, {
+    'Content-Type': 'text/event-stream',
+    'Cache-Control': 'no-cache',
+    'Connection': 'keep-alive',
+    // nginx would otherwise buffer the stream
+    'X-Accel-Buffering': 'no',
+  });
+  res.write(": connected\n\n");
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  if(url.searchParams.get("snapshot") === "1") {
+    // the current state to apply the following events on, so that nothing has to poll /pkg
+    res.write(`event: pkg\ndata: ${JSON.stringify(buildPkgData(new URL("/pkg", url)))}\n\n`);
+  }
+  eventStreams.add(res);
+
+  /**
+   * @param {import("./utils").HistoryInterface} entry
+   */
+  const onRecord = (entry) => {
+    if(res.writableLength > EVENTS_MAX_BUFFERED) {
+      verb(routeEventsHandler, "dropping a slow client");
+      res.destroy();
+      return;
+    }
+    res.write(`event: ${entry.action}\ndata: ${JSON.stringify(stripHistoryEntry(entry))}\n\n`);
+  };
+  // keeps proxies from closing an idle stream
+  const heartbeat = setInterval(() => res.write(": heartbeat\n\n"), 30e3);
+  historyEvents.on("record", onRecord);
+  res.on("close", () => {
+    historyEvents.off("record", onRecord);
+    clearInterval(heartbeat);
+    eventStreams.delete(res);
+  });
+}
+
+/**
+ * @param {http.IncomingMessage} req 
+ * @param {http.ServerResponse} res 
+ */
+async function routeMetricsHandler(req, res) {
+  verb(routeMetricsHandler);
+  // gauges are refreshed on every scrape
+  metrics.set("plct_assigned_packages", packageStatus.reduce((sum, user) => sum + user.packages.length, 0));
+  metrics.set("plct_packagers", packageStatus.filter(user => user.packages.length > 0).length);
+  metrics.set("plct_marked_packages", packageMarks.filter(pkg => pkg.marks.length > 0).length);
+  metrics.set("plct_pending_notifications", pendingNotifications.length);
+  res.writeHead(200, { 'Content-Type': 'text/plain; version=0.0.4' });
+  res.end(metrics.render());
+}
+
+/**
+ * @param {http.IncomingMessage} req 
+ * @param {http.ServerResponse} res 
+ */
+async function routeHealthHandler(req, res) {
+  verb(routeHealthHandler);
+  try {
+    await checkStorage(HEALTH_CHECK_TIMEOUT);
+  } catch(err) {
+    verb(routeHealthHandler, err.message);
+    res.writeHead(503, { 'Content-Type': 'application/json' });
+    res.end(JSON.stringify({ db: "error" }));
+    return;
+  }
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify({ db: "ok" }));
+}
+
+/**
+ * @param {http.IncomingMessage} req 
+ * @param {http.ServerResponse} res 
+ */
+async function routeHealthzHandler(req, res) {
+  verb(routeHealthzHandler);
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  /**
+   * @type {{ db: "ok" | "error"; telegram: "ok" | "error" | "skipped"; pendingNotifications: number; }}
+   */
+  const data = { db: "ok", telegram: "skipped", pendingNotifications: pendingNotifications.length };
+  const checks = [
+    checkStorage(HEALTH_CHECK_TIMEOUT).catch(err => {
+      verb(routeHealthzHandler, "db:", err.message);
+      data.db = "error";
+    }),
+  ];
+  if(HEALTHZ_CHECK_TELEGRAM || url.searchParams.get("telegram") === "1") {
+    checks.push(withTimeout(bot.getMe(), HEALTH_CHECK_TIMEOUT, "getMe").then(() => {
+      data.telegram = "ok";
+    }).catch(err => {
+      verb(routeHealthzHandler, "telegram:", err.message);
+      data.telegram = "error";
+    }));
+  }
+  await Promise.all(checks);
+  const healthy = data.db === "ok" && data.telegram !== "error";
+  res.writeHead(healthy ? 200 : 503, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(data));
+}
+
+const STATS_TOP_PACKAGERS = 5;
+
+/**
+ * @param {http.IncomingMessage} req 
+ * @param {http.ServerResponse} res 
+ */
+async function routeStatsHandler(req, res) {
+  verb(routeStatsHandler);
+  const assigned = new Set(packageStatus.flatMap(user => user.packages.map(pkg => pkg.name)));
+  /**
+   * @type {Record<string, number>}
+   */
+  const markCount = {};
+  // 所有 mark 都列出来，没人用的也是 0，方便画图
+  for(const mark of getAvailableMarks()) {
+    markCount[mark] = 0;
+  }
+  for(const pkg of packageMarks) {
+    for(const mark of pkg.marks) {
+      markCount[mark.name] = (markCount[mark.name] || 0) + 1;
+    }
+  }
+  const markedPackages = packageMarks.filter(pkg => pkg.marks.length > 0);
+  const packagers = packageStatus
+    .filter(user => user.packages.length > 0)
+    .map(user => ({
+      tgUid: user.userid,
+      alias: getAlias(user.userid),
+      count: user.packages.length,
+    }));
+  const data = {
+    packagers,
+    // sort() is stable, so ties keep the order of `packagers`
+    topPackagers: packagers.slice().sort((a, b) => b.count - a.count).slice(0, STATS_TOP_PACKAGERS),
+    marks: Object.keys(markCount).map(name => ({ name, count: markCount[name] })),
+    total: {
+      packagers: packageStatus.filter(user => user.packages.length > 0).length,
+      assignedPackages: assigned.size,
+      markedPackages: markedPackages.length,
+      unassignedMarkedPackages: markedPackages.filter(pkg => !assigned.has(pkg.name)).length,
+    },
+  };
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(data));
+}
+
+// how many of the oldest assignments the weekly report lists
+const WEEKLY_REPORT_OLDEST_COUNT = 10;
+
+/**
+ * @param {number} now timestamp (ms) the report ends at, it covers the 7 days before
+ * @returns {{ args: import("./_messages").WeeklyReportArgs; text: string; }} what is reported, with aliases,
+ * and the MarkdownV2 message of it
+ */
+function renderWeeklyReport(now) {
+  const since = now - 7 * 86400e3;
+  const report = buildWeeklyReport(localUtils.packageHistory, packageStatus, packageMarks, since, WEEKLY_REPORT_OLDEST_COUNT);
+  const today = getDayNumber(now);
+  const args = {
+    from: toLocalDate(since).toISOString().slice(0, 10),
+    to: toLocalDate(now).toISOString().slice(0, 10),
+    closed: report.closed.map(({ userid, count }) => ({ alias: userid === null ? null : getAlias(userid), count })),
+    newlyFailing: report.newlyFailing,
+    oldest: report.oldest.map(({ userid, pkgname, assignedAt }) => ({
+      pkgname,
+      alias: getAlias(userid),
+      days: today - getDayNumber(assignedAt),
+    })),
+  };
+  return { args, text: tgMessages.weeklyReport(args) };
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ */
+async function routeWeeklyReportHandler(req, res) {
+  verb(routeWeeklyReportHandler);
+  const { args, text } = renderWeeklyReport(Date.now());
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(Object.assign({}, args, { text })));
+}
+
+/**
+ * @param {http.IncomingMessage} req 
+ * @param {http.ServerResponse} res 
+ */
+async function routeDeleteHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const args = url.pathname.slice(1).split("/");
+  verb(routeDeleteHandler, args);
+
+  if(!isAuthorized(req, "delete")) {
+    throw new HttpError(403);
+  }
+  if(args.length != 3) {
+    throw new HttpError(400);
+  }
+  const pkgname = decodePkgname(args[1]), status = args[2];
+  if(!isDeleteStatus(status)) {
+    throw new HttpError(400, `unknown status ${status}; available: ${DELETE_STATUSES.join(", ")}`);
+  }
+  const chatId = getChatIdByArch(url);
+  // dry runs count against the limit too, so that a script looping on them is held back like any other
+  if(rejectIfDeleteRateLimited(res, getTokenName(req))) return;
+  if(["true", "1"].includes(url.searchParams.get("dry_run"))) {
+    res.writeHead(200, { 'Content-Type': 'application/json' });
+    res.end(JSON.stringify(Object.assign({ pkgname, status, chatId: getDestination("merge", chatId).chatId, alreadyDeleted: isAlreadyMerged(pkgname) }, planAutoMerge(pkgname, status))));
+    return;
+  }
+  const merged = await withDeleteLock(pkgname, async () => {
+    if(isAlreadyMerged(pkgname)) return null;
+    return await _autoMerge(pkgname, status, true, chatId, getRequestId(req));
+  });
+  if(merged !== null && merged.failures.length > 0) {
+    // report every failure instead of only the first one
+    throw new HttpError(500, merged.failures.join("; "));
+  }
+  const reply = mergePlan.getDeleteReply(merged, ["true", "1"].includes(url.searchParams.get("strict")));
+  if(reply === null) {
+    throw new HttpError(404, "package not assigned");
+  }
+  res.writeHead(200, { 'Content-Type': 'text/plain' });
+  res.end(reply);
+}
+
+/**
+ * @typedef {import("./utils").DeleteStatus} DeleteStatus
+ */
+
+/**
+ * @param {any} status
+ * @returns {status is DeleteStatus}
+ */
+function isDeleteStatus(status) {
+  return DELETE_STATUSES.includes(status);
+}
+
+// marks of other packages which point to the merged package in their comments
+const AUTO_MERGE_REF_MARKS = ["outdated_dep", "missing_dep"];
+
+/**
+ * @param {string} pkgname
+ * @description whether _autoMerge() has nothing left to do with `pkgname`
+ */
+function isAlreadyMerged(pkgname) {
+  if(localUtils.findUserIdByPackage(pkgname) !== null) return false;
+  const currMarks = getPackageMarkNamesByPkgname(pkgname);
+  if(getClearOnMergeMarks().some(mark => currMarks.includes(mark))) return false;
+  return findPackageMarksByMarkNamesAndComment(AUTO_MERGE_REF_MARKS, `[${pkgname}]`).length === 0;
+}
+
+/**
+ * @param {string} pkgname
+ * @param {DeleteStatus} status
+ * @param {boolean} ping whether to ping the packager, batch deletion sends one ping for all packages instead
+ * @param {string} [chatId] the chat of the request, see getChatIdByArch(); the messages go to its merge destination, failures to the admin one
+ * @param {string | null} [requestId] appended to failure messages, so that they can be found in the HTTP log
+ * @returns {Promise<{ userId: number | null; mergeFailReason: string | null; failures: string[]; }>}
+ * `failures` contains `mergeFailReason` and every failed mark or unmark
+ * @description 出包后的自动处理：释放认领记录，并清掉这个包以及别的包有关这个包的特定 mark
+ */
+async function _autoMerge(pkgname, status, ping, chatId = CHAT_ID, requestId = null) {
+  /**
+   * @type {string | null}
+   */
+  let mergeFailReason = null;
+  const mergeDestination = getDestination("merge", chatId);
+  /**
+   * @type {Promise<TelegramBot.Message | null>} the ping, which the follow-ups about `pkgname` reply to
+   */
+  let pingSent = Promise.resolve(null);
+
+  const userId = localUtils.findUserIdByPackage(pkgname);
+  verb(_autoMerge, "userId is", userId);
+  if(userId !== null) {
+    const alias = getAlias(userId);
+    const link = getMentionLink(userId, null, alias);
+
+    // only tell the packager once the assignment is really released
+    await new Promise((resolve) => _merge(pkgname, userId, (success, reason) => {
+      if(!success) {
+        mergeFailReason = reason;
+        sendMessage(getDestination("admin", chatId), tgMessages.mergeFailed({ reason, requestId }), {
+          parse_mode: "MarkdownV2",
+        }, true);
+      } else if(ping) {
+        pingSent = rememberPing(pkgname, mergeDestination, sendMessage(mergeDestination, tgMessages.mergedPing({ link, pkgname, status }), Object.assign({
+          parse_mode: "MarkdownV2",
+          disable_notification: false,
+        }, getReplyOptions(pkgname, mergeDestination)), true)).catch(() => null);
+      }
+      resolve();
+    }, API_ACTOR));
+  }
+
+  /**
+   * @type {string[]}
+   */
+  const failures = mergeFailReason === null ? [] : [mergeFailReason];
+
+  await (async function fencedAtomicOps() {
+  // 自动出包后，首先把这个包的特定 mark 清掉
+  // only try marks the package actually has, so that every failed unmark is a real failure
+  const currMarks = getPackageMarkNamesByPkgname(pkgname);
+  const targetMarks = getClearOnMergeMarks().filter(mark => currMarks.includes(mark));
+  /**
+   * @type {string[]}
+   */
+  const followUps = [];
+  // unmark one-by-one instead of _unmarkMultiple(), so that a failure can tell which mark it was
+  for(const mark of targetMarks) {
+    await _unmark(pkgname, mark, (success, reason) => {
+      if(!success) {
+        failures.push(`failed to unmark ${mark} of ${pkgname}: ${reason}`);
+        return;
+      }
+      followUps.push(tgMessages.mergedUnmark({ pkgname, status, mark }));
+    }, API_ACTOR);
+  }
+  if(followUps.length > 0) {
+    // appended to the ping once it is sent, or a reply to it (or to the last ping about the package) if that fails,
+    // so they may well arrive after the messages below
+    pingSent.then(async (message) => {
+      if(message && await appendToMessage(message, followUps.join("\n"))) return;
+      await sendMessage(mergeDestination, followUps.join("\n"), Object.assign({
+        parse_mode: "MarkdownV2",
+        // the ping before it already rings
+        disable_notification: true,
+      }, message ? { reply_to_message_id: message.message_id, allow_sending_without_reply: true } : getReplyOptions(pkgname, mergeDestination)), true);
+    }).catch(err => verb(_autoMerge, "failed to send the unmark follow-ups of", pkgname, err.message));
+  }
+
+  // 之后清掉别的包有关这个包的特定 mark
+  const refMarks = AUTO_MERGE_REF_MARKS;
+  const targetPackages = findPackageMarksByMarkNamesAndComment(refMarks, `[${pkgname}]`);
+  verb(_autoMerge, "other pkgs with marks pointing to", pkgname, ":", targetPackages);
+  for(const pkg of targetPackages) {
+    // 效果上需要先 Ping 后输出内容，但遍历完才能知道需要 Ping 谁，所以把输出手动 defer 到最后
+    const deferKey = crypto.randomBytes(16).toString("hex");
+    /**
+     * @type {Set<string>}
+     */
+    const mentionLinkSet = new Set();
+    for(const mark of pkg.marks.slice()) {
+      if(!refMarks.includes(mark.name)) continue;
+      let mentionLink = BOT_MENTION_LINK;
+      if(mark.by) {
+        // we don't use mark.by.url here, because it is based on tg name but we want alias
+        mentionLink = getMentionLink(mark.by.uid, null, getAlias(mark.by.uid));
+        mentionLinkSet.add(mentionLink);
+      }
+      const uid = mark.by ? mark.by.uid : BOT_ID;
+      if(mark.comment.toLowerCase() === `[${pkgname}]`.toLowerCase()) {
+        verb(_autoMerge, "(auto) full match:", pkgname, "is", pkg.name, mark.name, mark.comment);
+        await _unmark(pkg.name, mark.name, (success, _) => {
+          if(!success) {
+            verb(_autoMerge, "failed to unmark", pkg.name, mark.name, _);
+            failures.push(`failed to unmark ${mark.name} of ${pkg.name}: ${_}`);
+            return;
+          }
+          // defer 输出
+          defer.add(deferKey, () => {
+            sendMessage(mergeDestination, tgMessages.mergedRefUnmark({ pkgname: pkg.name, refPkgname: pkgname, status, mark: mark.name }), {
+              parse_mode: "MarkdownV2",
+            }, true);
+          });
+        }, API_ACTOR);
+      } else {
+        verb(_autoMerge, "(auto) partial match:", pkgname, "in", pkg.name, mark.name, mark.comment);
+        const safePkgname = escapeRegExp(pkgname);
+        const comment = mark.comment.replace(new RegExp("\\[" + safePkgname + "\\]", "i"), "").trim();
+        verb(_autoMerge, "built regexp name:", safePkgname, "-> new comment:", comment);
+        await _mark(pkg.name, mark.name, comment, uid, mentionLink, (success, _) => {
+          if(!success) {
+            verb(_autoMerge, "failed to mark", pkg.name, mark.name, mark.comment, _);
+            failures.push(`failed to update ${mark.name} of ${pkg.name}: ${_}`);
+            return;
+          }
+          // defer 输出
+          defer.add(deferKey, () => {
+            sendMessage(mergeDestination, tgMessages.mergedRefRemoved({ pkgname: pkg.name, refPkgname: pkgname, mark: mark.name }), {
+              parse_mode: "MarkdownV2",
+            }, true);
+          });
+        }, API_ACTOR);
+      }
+    }
+    if(mentionLinkSet.size > 0) {
+      // 先发送 ping 消息
+      sendMessage(mergeDestination, tgMessages.cc({ links: Array.from(mentionLinkSet) }), {
+        parse_mode: "MarkdownV2",
+        disable_notification: false,
+      }, true);
+      // 再发送此前被 defer 的输出
+      defer.resolve(deferKey);
+    }
+  }
+  })();  // invoke fencedAtomicOps()
+
+  metrics.inc("plct_delete_total", { result: failures.length === 0 ? "success" : "failure" });
+  return { userId, mergeFailReason, failures };
+}
+
+const deleteLock = new KeyedLock();
+
+/**
+ * @template T
+ * @param {string} pkgname
+ * @param {() => Promise<T>} fn
+ * @returns {Promise<T>}
+ * @description deletions of the same package run one after another, so that a concurrent one
+ * finds it already deleted instead of clearing the same marks again
+ */
+function withDeleteLock(pkgname, fn) {
+  return deleteLock.run(pkgname, fn);
+}
+
+/**
+ * @param {string} pkgname
+ * @param {DeleteStatus} status
+ * @description what _autoMerge() would do with the current assignments and marks, see _mergePlan.js
+ */
+function planAutoMerge(pkgname, status) {
+  return mergePlan.planAutoMerge(pkgname, status, {
+    packageStatus,
+    packageMarks,
+    clearOnMergeMarks: getClearOnMergeMarks(),
+    refMarks: AUTO_MERGE_REF_MARKS,
+    getAlias,
+    messages: tgMessages,
+  });
+}
+
+const DELETE_BATCH_MAX_SIZE = 100;
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ * @description the body is either `{ token, packages }` or just the array of packages
+ */
+async function routeDeleteBatchHandler(req, res) {
+  verb(routeDeleteBatchHandler);
+  let body;
+  try {
+    body = await readJsonBody(req);
+  } catch(err) {
+    verb(routeDeleteBatchHandler, "failed to read body:", err.message);
+    throw new HttpError(400);
+  }
+  if(!isAuthorized(req, "delete", body ? body.token : undefined)) {
+    throw new HttpError(403);
+  }
+  const packages = Array.isArray(body) ? body : body ? body.packages : undefined;
+  if(!Array.isArray(packages)) {
+    throw new HttpError(400);
+  }
+  if(packages.length > DELETE_BATCH_MAX_SIZE) {
+    throw new HttpError(400, `at most ${DELETE_BATCH_MAX_SIZE} packages per batch`);
+  }
+  const chatId = getChatIdByArch(new URL(req.url, `http://${req.headers.host}`));
+  // a batch sends a single ping, so it counts as one call
+  if(rejectIfDeleteRateLimited(res, getTokenName(req, body.token))) return;
+
+  /**
+   * @type {{ pkgname: any; status: any; success: boolean; detail: string; }[]}
+   */
+  const results = [];
+  /**
+   * @type {Map<number, Map<DeleteStatus, string[]>>} userId -> status -> merged packages
+   */
+  const mergedPackages = new Map();
+  for(const entry of packages) {
+    const pkgname = entry ? entry.pkgname : undefined, status = entry ? entry.status : undefined;
+    if(typeof pkgname !== "string") {
+      results.push({ pkgname, status, success: false, detail: "Bad Request" });
+      continue;
+    }
+    if(!isDeleteStatus(status)) {
+      results.push({ pkgname, status, success: false, detail: `Bad Request: unknown status ${status}` });
+      continue;
+    }
+    if(!isValidPkgname(pkgname)) {
+      results.push({ pkgname, status, success: false, detail: "Bad Request: invalid pkgname" });
+      continue;
+    }
+    // one by one, the same as calling /delete in a loop
+    const merged = await withDeleteLock(pkgname, async () => {
+      if(isAlreadyMerged(pkgname)) return null;
+      return await _autoMerge(pkgname, status, false, chatId, getRequestId(req));
+    });
+    if(merged === null) {
+      results.push({ pkgname, status, success: true, detail: "already deleted" });
+      continue;
+    }
+    const { userId, mergeFailReason, failures } = merged;
+    if(userId !== null && mergeFailReason === null) {
+      const byStatus = mergedPackages.get(userId) || new Map();
+      byStatus.set(status, (byStatus.get(status) || []).concat(pkgname));
+      mergedPackages.set(userId, byStatus);
+    }
+    if(failures.length > 0) {
+      results.push({ pkgname, status, success: false, detail: `Internal Server Error: ${failures.join("; ")}` });
+      continue;
+    }
+    results.push({ pkgname, status, success: true, detail: (userId === null ? "package not found;" : "") + "success" });
+  }
+
+  // ping every packager in a single message, instead of one message per package
+  if(mergedPackages.size > 0) {
+    const lines = [];
+    for(const [userId, byStatus] of mergedPackages) {
+      const link = getMentionLink(userId, null, getAlias(userId));
+      const groups = Array.from(byStatus, ([status, pkgnames]) => ({ pkgnames, status }));
+      lines.push(tgMessages.mergedPingBatch({ link, groups }));
+    }
+    sendMessage(getDestination("merge", chatId), lines.join("\n"), {
+      parse_mode: "MarkdownV2",
+      disable_notification: false,
+    }, true);
+  }
+
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(results));
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ */
+async function routeAddHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const args = url.pathname.slice(1).split("/");
+  verb(routeAddHandler, args);
+
+  if(!isAuthorized(req, "add")) {
+    throw new HttpError(403);
+  }
+  if(args.length != 3) {
+    throw new HttpError(400);
+  }
+  const pkgname = decodePkgname(args[1]), status = args[2];
+  if(status !== "ftbfs") {
+    throw new HttpError(400);
+  }
+  const destination = getDestination("marks", getChatIdByArch(url));
+  res.writeHead(200, { 'Content-Type': 'text/plain' });
+
+  const userId = localUtils.findUserIdByPackage(pkgname);
   if(userId === null) {
     res.write("package not found; ");
   } else {
     const alias = getAlias(userId);
     const link = getMentionLink(userId, null, alias);
-    const msgTypeStr = wrapCode("(auto-mark)");
-    const failingLogLink = getErrorLogDirLinkMd(pkgname, "is failing");
+    const logLink = getErrorLogDirLinkMd(pkgname, "is failing");
     // Ping 先输出，剩下的输出全部 defer
-    sendMessage(CHAT_ID, _safemd`${msgTypeStr} ping ${link}: ${toSafeMd(pkgname)} ${failingLogLink}`, {
+    rememberPing(pkgname, destination, sendMessage(destination, tgMessages.failingPing({ link, pkgname, logLink }), Object.assign({
+      parse_mode: "MarkdownV2",
+      disable_notification: false,
+    }, getReplyOptions(pkgname, destination)), true)).catch(() => {});
+  }
+
+  const deferKey = crypto.randomBytes(16).toString("hex");
+
+  const mentionLink = BOT_MENTION_LINK;
+  let shouldSendMsg = true;
+  if(findPackageMarksByMarkName("failing").filter(pkg => pkg.name === pkgname).length > 0) {
+    shouldSendMsg = false;
+  }
+  await _mark(pkgname, "failing", getCurrentTimeStr(), BOT_ID, mentionLink, (success, _) => {
+    if(!success) return;
+    // 对于已存在 failing 标记的情况，需要更新 comment 内的时间（getCurrentTimeStr()），但不输出
+    if(!shouldSendMsg) return;
+    const logLink = getErrorLogDirLinkMd(pkgname, "failing");
+    // defer 输出
+    defer.add(deferKey, () => {
+      sendMessage(destination, tgMessages.failingMarked({ pkgname, logLink }), {
+        parse_mode: "MarkdownV2",
+      }, true);
+    });
+  }, API_ACTOR);
+
+  await _unmarkMultiple(pkgname, ["ready"], (success, reason) => {
+    if(!success) return;
+    // for success === true, `reason` is the name of the modified mark
+    const mark = reason;
+    // defer 输出
+    defer.add(deferKey, () => {
+      sendMessage(destination, tgMessages.unmarked({ pkgname, mark }), {
+        parse_mode: "MarkdownV2",
+      }, true);
+    });
+  }, API_ACTOR);
+
+  res.end("success");
+  await defer.resolve(deferKey);
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ */
+async function routeMarkHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const args = url.pathname.slice(1).split("/");
+  verb(routeMarkHandler, args);
+
+  let body;
+  try {
+    body = await readJsonBody(req);
+  } catch(err) {
+    verb(routeMarkHandler, "failed to read body:", err.message);
+    throw new HttpError(400);
+  }
+  // an array sets several marks at once, its token can only come from the headers
+  const isBatch = Array.isArray(body);
+  if(!isAuthorized(req, "mark", body && !isBatch ? body.token : undefined)) {
+    throw new HttpError(403);
+  }
+  // POST /mark/{pkgname}, or POST /mark with the pkgname in the body
+  const hasPathPkgname = args.length === 2 && args[1] !== "";
+  if(!body || (!hasPathPkgname && (isBatch || args.length !== 1 || typeof body.pkgname !== "string"))) {
+    throw new HttpError(400);
+  }
+  const items = getMarkItems(body);
+  if(!hasPathPkgname && !isValidPkgname(body.pkgname)) {
+    throw new HttpError(400, `invalid pkgname ${JSON.stringify(body.pkgname.slice(0, 64))}`);
+  }
+  const pkgname = hasPathPkgname ? decodePkgname(args[1]) : body.pkgname;
+  const pkgMarks = packageMarks.find(pkg => pkg.name === pkgname);
+
+  // check every mark before changing anything, so that one bad mark rejects the whole request
+  const pending = planMarks(items, pkgMarks ? pkgMarks.marks : [], getMarkConfigOrThrow, getCurrentTimeStr());
+  if(pending.length === 0) {
+    res.writeHead(200, { 'Content-Type': 'text/plain' });
+    res.end("already marked");
+    return;
+  }
+
+  // cascading marks & unmarks only touch this package, so its marks are all there is to roll back
+  const savedMarks = pkgMarks ? pkgMarks.marks.map(markObj => Object.assign({}, markObj)) : [];
+  const savedHistoryLength = localUtils.packageHistory.length;
+  /**
+   * @type {string[]} sent only once every mark is stored
+   */
+  const unmarkMessages = [];
+  /**
+   * @type {string | null}
+   */
+  let markFailReason = null;
+  for(const { mark, comment, markConfig } of pending) {
+    // cascading marks & unmarks，和 /mark 命令一致
+    if(markConfig.triggers.length > 0) {
+      const { shouldMark, shouldUnmark } = getTriggeredMarks(pkgname, markConfig, "mark");
+      if(shouldMark.length > 0) {
+        verb(routeMarkHandler, `triggered by this mark: should also mark`, shouldMark);
+        const comments = shouldMark.map(() => `cascading mark triggered by marking ${mark}`);
+        await _markMultiple(pkgname, BOT_ID, BOT_MENTION_LINK, shouldMark, comments, (success, reason) => {
+          if(!success) markFailReason = reason;
+        }, API_ACTOR);
+      }
+      if(shouldUnmark.length > 0) {
+        verb(routeMarkHandler, `triggered by this mark: should also unmark`, shouldUnmark);
+        await _unmarkMultiple(pkgname, shouldUnmark, (success, reason) => {
+          if(!success) {
+            markFailReason = reason;
+            return;
+          }
+          unmarkMessages.push(tgMessages.unmarked({ pkgname, mark: reason }));
+        }, API_ACTOR);
+      }
+    }
+    await _mark(pkgname, mark, comment, BOT_ID, BOT_MENTION_LINK, (success, reason) => {
+      if(!success) markFailReason = reason;
+    }, API_ACTOR);
+    if(markFailReason !== null) break;
+  }
+
+  if(markFailReason !== null) {
+    verb(routeMarkHandler, "rolling back the marks of", pkgname, "because of", markFailReason);
+    const target = packageMarks.find(pkg => pkg.name === pkgname);
+    if(target) target.marks = savedMarks;
+    truncateHistory(savedHistoryLength);
+    await Promise.all([storePackageMarks(), storePackageHistory()]).catch(err => verb(routeMarkHandler, "failed to roll back:", err));
+    throw new HttpError(500, markFailReason);
+  }
+  unmarkMessages.forEach(text => sendMessage(getDestination("marks"), text, { parse_mode: "MarkdownV2" }, true));
+  sendMessage(getDestination("marks"), tgMessages.markAdded({ pkgname, marks: pending }), {
+    parse_mode: "MarkdownV2",
+  }, true);
+  res.writeHead(200, { 'Content-Type': 'text/plain' });
+  res.end("success");
+}
+
+/**
+ * @param {string} mark
+ * @description the config of `mark`, shared by the mark routes; throws a 400 HttpError for unknown marks
+ */
+function getMarkConfigOrThrow(mark) {
+  const markConfig = getMarkConfig(mark);
+  if(!markConfig) {
+    throw new HttpError(400, `unknown mark ${mark}; available: ${getAvailableMarks().join(", ")}`);
+  }
+  return markConfig;
+}
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ * @description DELETE /mark/{pkgname}/{mark}, responds with the names of the removed marks, cascading unmarks included
+ */
+async function routeUnmarkHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const args = url.pathname.slice(1).split("/");
+  verb(routeUnmarkHandler, args);
+
+  if(!isAuthorized(req, "mark")) {
+    throw new HttpError(403);
+  }
+  if(args.length != 3 || args[2] === "") {
+    throw new HttpError(400);
+  }
+  const pkgname = decodePkgname(args[1]), mark = args[2];
+  const markConfig = getMarkConfigOrThrow(mark);
+  if(!getPackageMarkNamesByPkgname(pkgname).includes(mark)) {
+    throw new HttpError(404, `${pkgname} is not marked as ${mark}`);
+  }
+
+  const removedMarks = [];
+  // cascading marks & unmarks，和 /unmark 命令一致
+  if(markConfig.triggers.length > 0) {
+    const { shouldMark, shouldUnmark } = getTriggeredMarks(pkgname, markConfig, "unmark");
+    if(shouldMark.length > 0) {
+      verb(routeUnmarkHandler, `triggered by this unmark: should also mark`, shouldMark);
+      const comments = shouldMark.map(() => `cascading mark triggered by unmarking ${mark}`);
+      await _markMultiple(pkgname, BOT_ID, BOT_MENTION_LINK, shouldMark, comments, () => {}, API_ACTOR);
+    }
+    if(shouldUnmark.length > 0) {
+      verb(routeUnmarkHandler, `triggered by this unmark: should also unmark`, shouldUnmark);
+      await _unmarkMultiple(pkgname, shouldUnmark, (success, reason) => {
+        if(!success) return;
+        removedMarks.push(reason);
+        sendMessage(getDestination("marks"), tgMessages.unmarked({ pkgname, mark: reason }), {
+          parse_mode: "MarkdownV2",
+        }, true);
+      }, API_ACTOR);
+    }
+  }
+
+  /**
+   * @type {string | null}
+   */
+  let unmarkFailReason = null;
+  await _unmark(pkgname, mark, (success, reason) => {
+    if(!success) {
+      unmarkFailReason = reason;
+      return;
+    }
+    removedMarks.unshift(mark);
+    sendMessage(getDestination("marks"), tgMessages.unmarked({ pkgname, mark }), {
       parse_mode: "MarkdownV2",
     }, true);
+  }, API_ACTOR);
+
+  if(unmarkFailReason !== null) {
+    throw new HttpError(500, unmarkFailReason);
   }
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(removedMarks));
+}
 
-  const deferKey = crypto.randomBytes(16).toString("hex");
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ */
+async function routeReassignHandler(req, res) {
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  const args = url.pathname.slice(1).split("/");
+  verb(routeReassignHandler, args);
 
-  const mentionLink = BOT_MENTION_LINK;
-  let shouldSendMsg = true;
-  if(findPackageMarksByMarkName("failing").filter(pkg => pkg.name === pkgname).length > 0) {
-    shouldSendMsg = false;
+  let body;
+  try {
+    body = await readJsonBody(req);
+  } catch(err) {
+    verb(routeReassignHandler, "failed to read body:", err.message);
+    throw new HttpError(400);
+  }
+  if(!isAuthorized(req, "reassign", body ? body.token : undefined)) {
+    throw new HttpError(403);
+  }
+  if(!body || args.length != 2 || args[1] === "" || !Number.isSafeInteger(body.new_tg_uid) ||
+    (body.new_alias !== undefined && (typeof body.new_alias !== "string" || body.new_alias === ""))) {
+    throw new HttpError(400);
+  }
+  const pkgname = decodePkgname(args[1]);
+  /**
+   * @type {number}
+   */
+  const newUserId = body.new_tg_uid;
+  const oldUser = packageStatus.find(user => user.packages.some(pkg => pkg.name === pkgname));
+  if(!oldUser) {
+    throw new HttpError(404, "package not assigned");
+  }
+  if(oldUser.userid === newUserId) {
+    throw new HttpError(400, "package already assigned to this packager");
+  }
+  if(body.new_alias !== undefined && body.new_alias !== getAlias(newUserId)) {
+    try {
+      await setAlias(newUserId, body.new_alias);
+    } catch(err) {
+      throw new HttpError(500, String(err));
+    }
   }
-  await _mark(pkgname, "failing", getCurrentTimeStr(), BOT_ID, mentionLink, (success, _) => {
-    if(!success) return;
-    // 对于已存在 failing 标记的情况，需要更新 comment 内的时间（getCurrentTimeStr()），但不输出
-    if(!shouldSendMsg) return;
-    const msgTypeStr = wrapCode("(auto-mark)");
-    const failingLogLink = getErrorLogDirLinkMd(pkgname, "failing");
-    // defer 输出
-    defer.add(deferKey, () => {
-      sendMessage(CHAT_ID, _safemd`${msgTypeStr} ${toSafeMd(pkgname)} 已被自动标记为 ${failingLogLink}`, {
-        parse_mode: "MarkdownV2",
-      }, true);
-    });
-  });
 
-  await _unmarkMultiple(pkgname, ["ready"], (success, reason) => {
-    if(!success) return;
-    // for success === true, `reason` is the name of the modified mark
-    const mark = reason;
-    const msgTypeStr = wrapCode("(auto-unmark)")
-    // defer 输出
-    defer.add(deferKey, () => {
-      sendMessage(CHAT_ID, msgTypeStr + toSafeMd(` ${pkgname} 不再被标记为 ${mark}`), {
-        parse_mode: "MarkdownV2",
-      }, true);
-    });
-  });
+  const savedHistoryLength = localUtils.packageHistory.length;
+  const undoMove = movePackage(packageStatus, pkgname, newUserId, Date.now());
+  recordHistory(pkgname, "reassign", API_ACTOR, `${oldUser.userid} -> ${newUserId}`);
+  try {
+    await Promise.all([storePackageStatus(), storePackageHistory()]);
+  } catch(err) {
+    // otherwise the exit hook would store the move the client was told failed
+    verb(routeReassignHandler, "rolling back the reassign of", pkgname, "because of", err);
+    undoMove();
+    truncateHistory(savedHistoryLength);
+    await Promise.all([storePackageStatus(), storePackageHistory()]).catch(err => verb(routeReassignHandler, "failed to roll back:", err));
+    throw new HttpError(500, String(err));
+  }
+
+  const oldLink = getMentionLink(oldUser.userid, null, getAlias(oldUser.userid));
+  const newLink = getMentionLink(newUserId, null, getAlias(newUserId));
+  sendMessage(CHAT_ID, tgMessages.reassigned({ oldLink, newLink, pkgname, oldAlias: getAlias(oldUser.userid), newAlias: getAlias(newUserId) }), {
+    parse_mode: "MarkdownV2",
+    disable_notification: false,
+  }, true);
+  res.writeHead(200, { 'Content-Type': 'text/plain' });
+  res.end("success");
+}
+
+// parts of a longer announcement are sent one after another, each at most this long
+const NOTIFY_PART_LENGTH = 4000;
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ */
+async function routeNotifyHandler(req, res) {
+  verb(routeNotifyHandler);
+
+  let body;
+  try {
+    body = await readJsonBody(req);
+  } catch(err) {
+    verb(routeNotifyHandler, "failed to read body:", err.message);
+    throw new HttpError(400);
+  }
+  if(!isAuthorized(req, "notify", body ? body.token : undefined)) {
+    throw new HttpError(403);
+  }
+  if(!body || typeof body.text !== "string" || body.text.trim() === "") {
+    throw new HttpError(400, "text is required");
+  }
+
+  // the text is sent as written, nothing in it is taken as markup
+  const parts = splitLongText(toSafeMd(body.text), NOTIFY_PART_LENGTH);
+  /**
+   * @type {number[]}
+   */
+  const messageIds = [];
+  for(const part of parts) {
+    let message;
+    try {
+      message = await sendMessage(CHAT_ID, part, { parse_mode: "MarkdownV2", disable_notification: false });
+    } catch(err) {
+      verb(routeNotifyHandler, "failed to send part", messageIds.length + 1, "of", parts.length, err.message);
+      throw new HttpError(502, `sent ${messageIds.length} of ${parts.length} parts: ${err.message}`);
+    }
+    messageIds.push(message.message_id);
+  }
+  res.writeHead(200, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify({ message_id: messageIds[0], message_ids: messageIds }));
+}
 
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ */
+async function routeTelegramWebhookHandler(req, res) {
+  verb(routeTelegramWebhookHandler);
+  if(!verifyToken(req.headers["x-telegram-bot-api-secret-token"], TELEGRAM_WEBHOOK_SECRET)) {
+    throw new HttpError(403);
+  }
+  let update;
+  try {
+    update = await readJsonBody(req);
+  } catch(err) {
+    verb(routeTelegramWebhookHandler, "failed to read body:", err.message);
+  }
+  if(!update || typeof update !== "object" || typeof update.update_id !== "number") {
+    throw new HttpError(400);
+  }
+  // dispatched to the same onText() handlers as polled updates
+  bot.processUpdate(update);
+  res.writeHead(200, { 'Content-Type': 'text/plain' });
   res.end("success");
-  await defer.resolve(deferKey);
+}
+
+// token bucket per client: RATE_LIMIT_BURST requests at once, refilled by RATE_LIMIT_PER_SECOND
+const RATE_LIMIT_BURST = Number(process.env["PLCT_RATE_LIMIT_BURST"] || 10);
+const RATE_LIMIT_PER_SECOND = Number(process.env["PLCT_RATE_LIMIT_PER_SECOND"] || 1);
+// behind a reverse proxy every request comes from the proxy, so use X-Forwarded-For instead,
+// for both the rate limits and the access log
+const TRUST_PROXY = process.env["PLCT_TRUST_PROXY"] === "1";
+const TRUSTED_PROXIES = (process.env["PLCT_TRUSTED_PROXIES"] || "127.0.0.1,::1").split(",").map(addr => addr.trim()).filter(Boolean);
+
+/**
+ * @param {http.IncomingMessage} req
+ */
+function getRequestIp(req) {
+  return getClientIp(req.socket.remoteAddress, req.headers["x-forwarded-for"], TRUST_PROXY, TRUSTED_PROXIES);
+}
+/**
+ * @type {Map<string, { tokens: number; updatedAt: number; }>}
+ */
+const rateLimitBuckets = new Map();
+
+// every /delete pings the group, so it is also limited per token, to keep a looping script
+// from getting the whole chat rate limited by Telegram
+const DELETE_RATE_LIMIT = Number(process.env["PLCT_DELETE_RATE_LIMIT"] || 30);
+const DELETE_RATE_LIMIT_WINDOW = Number(process.env["PLCT_DELETE_RATE_LIMIT_WINDOW"] || 60) * 1000;
+const deleteRateLimiter = new SlidingWindowLimiter(DELETE_RATE_LIMIT, DELETE_RATE_LIMIT_WINDOW);
+
+// full buckets are the same as no bucket, drop them so that the map doesn't grow forever
+setInterval(() => {
+  const now = Date.now();
+  for(const [key, bucket] of rateLimitBuckets) {
+    if(bucket.tokens + (now - bucket.updatedAt) / 1000 * RATE_LIMIT_PER_SECOND >= RATE_LIMIT_BURST) {
+      rateLimitBuckets.delete(key);
+    }
+  }
+  deleteRateLimiter.prune();
+}, 60e3);
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ * @returns {boolean} true if the request is rate limited and has been answered with 429
+ */
+function rejectIfRateLimited(req, res) {
+  const key = getRequestIp(req);
+  const now = Date.now();
+  const bucket = rateLimitBuckets.get(key) || { tokens: RATE_LIMIT_BURST, updatedAt: now };
+  bucket.tokens = Math.min(RATE_LIMIT_BURST, bucket.tokens + (now - bucket.updatedAt) / 1000 * RATE_LIMIT_PER_SECOND);
+  bucket.updatedAt = now;
+  rateLimitBuckets.set(key, bucket);
+  if(bucket.tokens >= 1) {
+    bucket.tokens -= 1;
+    return false;
+  }
+  const retryAfter = Math.ceil((1 - bucket.tokens) / RATE_LIMIT_PER_SECOND);
+  verb(rejectIfRateLimited, key, "is rate limited, retry after", retryAfter, "s");
+  res.writeHead(429, { 'Content-Type': 'text/plain', 'Retry-After': String(retryAfter) });
+  res.end("Too Many Requests");
+  return true;
+}
+
+/**
+ * @param {http.ServerResponse} res
+ * @param {string} tokenName see getTokenName()
+ * @returns {boolean} true if the token has used up its DELETE_RATE_LIMIT calls in the sliding window,
+ * and the request has been answered with 429
+ */
+function rejectIfDeleteRateLimited(res, tokenName) {
+  const retryAfter = deleteRateLimiter.take(tokenName);
+  if(retryAfter === null) {
+    return false;
+  }
+  verb(rejectIfDeleteRateLimited, `token "${tokenName}"`, "is rate limited, retry after", retryAfter, "s");
+  res.writeHead(429, { 'Content-Type': 'text/plain', 'Retry-After': String(retryAfter) });
+  res.end("Too Many Requests");
+  return true;
+}
+
+/**
+ * @param {URL} url
+ * @returns {string} the query string with the token redacted, never log the raw token
+ */
+function redactSearchParams(url) {
+  const searchParams = new URLSearchParams(url.searchParams);
+  if(searchParams.has("token")) {
+    searchParams.set("token", "REDACTED");
+  }
+  const search = searchParams.toString();
+  return search ? "?" + search : "";
+}
+
+let inFlightRequestCount = 0;
+
+/**
+ * @type {WeakMap<http.IncomingMessage, string>}
+ */
+const requestIds = new WeakMap();
+
+/**
+ * @param {http.IncomingMessage} req
+ * @description the X-Request-Id set by the reverse proxy (e.g. nginx `$request_id`), or a random one
+ */
+function getRequestId(req) {
+  let requestId = requestIds.get(req);
+  if(requestId === undefined) {
+    const proxyRequestId = req.headers["x-request-id"];
+    requestId = typeof proxyRequestId === "string" && /^[\w-]{1,64}$/.test(proxyRequestId)
+      ? proxyRequestId : crypto.randomBytes(8).toString("hex");
+    requestIds.set(req, requestId);
+  }
+  return requestId;
+}
+
+// a CI retrying a /delete whose response got lost would otherwise ping the group twice
+const IDEMPOTENCY_KEY_TTL = Number(process.env["PLCT_IDEMPOTENCY_KEY_TTL"] || 86400) * 1000;
+const IDEMPOTENCY_MAX_KEYS = 1000;
+/**
+ * @type {IdempotencyCache<{ status: number; headers: http.OutgoingHttpHeaders; body: string; }>}
+ * responses by method, path, query and Idempotency-Key
+ */
+const idempotentResponses = new IdempotencyCache(IDEMPOTENCY_KEY_TTL, IDEMPOTENCY_MAX_KEYS);
+
+/**
+ * @param {http.IncomingMessage} req
+ * @param {http.ServerResponse} res
+ * @param {() => Promise<void>} run handles the request
+ * @description answers a request whose Idempotency-Key has been seen with the stored response, instead of
+ * running it again. Only 2xx responses are stored, so that a failed request can be retried with the same key
+ */
+async function withIdempotencyKey(req, res, run) {
+  const idempotencyKey = req.headers["idempotency-key"];
+  if(typeof idempotencyKey !== "string" || idempotencyKey === "") {
+    return run();
+  }
+  const url = new URL(req.url, `http://${req.headers.host}`);
+  // the query is part of it, e.g. a dry_run isn't the real thing
+  const key = `${req.method} ${url.pathname}${redactSearchParams(url)} ${idempotencyKey}`;
+  const { replayed, response } = await idempotentResponses.run(key, async () => {
+    let body = "";
+    const end = res.end.bind(res);
+    // @ts-ignore the handlers only call end() with the whole body or nothing
+    res.end = (chunk, ...rest) => {
+      body = chunk === undefined || typeof chunk === "function" ? "" : String(chunk);
+      return end(chunk, ...rest);
+    };
+    await run();
+    return res.statusCode >= 200 && res.statusCode < 300 ? { status: res.statusCode, headers: res.getHeaders(), body } : null;
+  });
+  if(replayed && response) {
+    verb(withIdempotencyKey, "replaying the response of", key);
+    const headers = Object.assign({}, response.headers, { "x-request-id": getRequestId(req), "idempotent-replayed": "true" });
+    res.writeHead(response.status, headers);
+    res.end(response.body);
+  }
+}
+
+const READ_METHODS = ["GET", "HEAD"];
+/**
+ * @type {Record<string, string[]>} route -> methods its handlers serve, /delete is special-cased in getAllowedMethods()
+ */
+const ROUTE_METHODS = {
+  "": READ_METHODS,
+  pkg: READ_METHODS,
+  marks: READ_METHODS,
+  assign: READ_METHODS,
+  packager: READ_METHODS,
+  stats: READ_METHODS,
+  report: READ_METHODS,
+  health: READ_METHODS,
+  healthz: READ_METHODS,
+  dashboard: READ_METHODS,
+  events: READ_METHODS,
+  history: READ_METHODS,
+  audit: READ_METHODS,
+  metrics: READ_METHODS,
+  add: READ_METHODS,
+  reassign: ["POST"],
+  notify: ["POST"],
+  telegram: ["POST"],
+  mark: ["POST", "DELETE"],
+};
+
+/**
+ * @param {string} route the first segment of the path
+ * @param {string[]} args
+ * @returns {string[] | null} for the Allow header of 405 responses, null for unknown routes
+ */
+function getAllowedMethods(route, args) {
+  if(route === "delete") {
+    // POST /delete and POST /delete/batch, GET /delete/{pkgname}/{status}
+    return args.length === 1 || (args.length === 2 && args[1] === "batch") ? ["POST"] : READ_METHODS;
+  }
+  if((route === "telegram" && !TELEGRAM_WEBHOOK_SECRET) || (route === "metrics" && METRICS_PORT)) {
+    // disabled, so they stay 404 whatever the method
+    return null;
+  }
+  return Object.prototype.hasOwnProperty.call(ROUTE_METHODS, route) ? ROUTE_METHODS[route] : null;
 }
 
 const server = http.createServer((req, res) => {
+  const startTime = Date.now();
+  const requestId = getRequestId(req);
+  res.setHeader("X-Request-Id", requestId);
+  inFlightRequestCount++;
+  res.on("close", () => inFlightRequestCount--);
   const url = new URL(req.url, `http://${req.headers.host}`);
   const args = url.pathname.slice(1).split("/");
   const route = args[0];
+  // the matched route pattern instead of the concrete path, so that logs can be aggregated
+  let routePattern = "(not found)";
+  /**
+   * @param {(req: http.IncomingMessage, res: http.ServerResponse) => Promise<void>} handler
+   */
+  const handle = (handler) => handler(req, res).catch((err) => {
+    if(!(err instanceof HttpError)) verb("http:", requestId, routePattern, "failed:", err);
+    respondError(res, err);
+  });
+  /**
+   * @param {(req: http.IncomingMessage, res: http.ServerResponse) => Promise<void>} handler
+   * @description for the routes changing anything, which honor Idempotency-Key
+   */
+  const handleMutation = (handler) => withIdempotencyKey(req, res, () => handle(handler));
+  res.on("finish", () => {
+    verb("http:", requestId, getRequestIp(req), req.method, routePattern + redactSearchParams(url),
+      res.statusCode, `${Date.now() - startTime}ms`);
+    metrics.inc("plct_http_requests_total", { route: routePattern, status: res.statusCode });
+  });
+  if(url.searchParams.has("token") && req.headers["authorization"] === undefined) {
+    // so that owners of old scripts notice, without breaking those comparing the body with "success"
+    res.setHeader("Warning", '299 - "?token= is deprecated, send Authorization: Bearer <token> instead"');
+  }
+  const allowedMethods = getAllowedMethods(route, args);
+  if(allowedMethods !== null && !allowedMethods.includes(req.method)) {
+    routePattern = "(method not allowed)";
+    res.setHeader("Allow", allowedMethods.join(", "));
+    respondError(res, new HttpError(405));
+    return;
+  }
   switch(route) {
     case "pkg":
-      routePkgHandler(req, res);
+      if(args.length > 1 && args[1] !== "") {
+        routePattern = "/pkg/{pkgname}";
+        handle(routePkgDetailHandler);
+      } else {
+        routePattern = "/pkg";
+        handle(routePkgHandler);
+      }
+      break;
+    case "marks":
+      // shadows a package named "stale", which is not an Arch package anyway
+      if(args.length === 2 && args[1] === "stale") {
+        routePattern = "/marks/stale";
+        handle(routeStaleMarksHandler);
+        break;
+      }
+      routePattern = "/marks/{pkgname}";
+      handle(routeMarksHandler);
+      break;
+    case "assign":
+      if(args.length >= 2 && args[1] === "check") {
+        routePattern = "/assign/check/{pkgname}";
+        handle(routeAssignCheckHandler);
+        break;
+      }
+      res.writeHead(404, { 'Content-Type': 'text/plain' });
+      res.end("Not Found");
+      break;
+    case "packager":
+      routePattern = "/packager/{packager}";
+      handle(routePackagerHandler);
+      break;
+    case "stats":
+      routePattern = "/stats";
+      handle(routeStatsHandler);
+      break;
+    case "report":
+      if(args.length === 2 && args[1] === "weekly") {
+        routePattern = "/report/weekly";
+        handle(routeWeeklyReportHandler);
+        break;
+      }
+      res.writeHead(404, { 'Content-Type': 'text/plain' });
+      res.end("Not Found");
+      break;
+    case "health":
+      routePattern = "/health";
+      handle(routeHealthHandler);
+      break;
+    case "healthz":
+      routePattern = "/healthz";
+      handle(routeHealthzHandler);
+      break;
+    case "":
+    case "dashboard":
+      if(args.length === 1) {
+        routePattern = "/" + route;
+        if(route === "") {
+          res.setHeader("Vary", "Accept");
+        }
+        // browsers get the dashboard at /, scripts get the landing JSON
+        handle(route === "" && !String(req.headers.accept || "").includes("text/html") ? routeRootHandler : routeDashboardHandler);
+        break;
+      }
+      res.writeHead(404, { 'Content-Type': 'text/plain' });
+      res.end("Not Found");
+      break;
+    case "events":
+      routePattern = "/events";
+      handle(routeEventsHandler);
+      break;
+    case "history":
+    case "audit":
+      routePattern = "/" + route;
+      handle(routeHistoryHandler);
+      break;
+    case "metrics":
+      if(METRICS_PORT) {
+        // only served by the metrics server then
+        res.writeHead(404, { 'Content-Type': 'text/plain' });
+        res.end("Not Found");
+        break;
+      }
+      routePattern = "/metrics";
+      handle(routeMetricsHandler);
       break;
     case "delete":
-      routeDeleteHandler(req, res);
+      if(req.method === "POST") {
+        routePattern = "/delete/batch";
+        if(rejectIfRateLimited(req, res)) break;
+        handleMutation(routeDeleteBatchHandler);
+      } else {
+        routePattern = "/delete/{pkgname}/{status}";
+        if(rejectIfRateLimited(req, res)) break;
+        handleMutation(routeDeleteHandler);
+      }
       break;
     case "add":
-      routeAddHandler(req, res);
+      routePattern = "/add/{pkgname}/{status}";
+      if(rejectIfRateLimited(req, res)) break;
+      handleMutation(routeAddHandler);
+      break;
+    case "reassign":
+      routePattern = "/reassign/{pkgname}";
+      if(rejectIfRateLimited(req, res)) break;
+      handleMutation(routeReassignHandler);
+      break;
+    case "notify":
+      if(args.length !== 1) {
+        res.writeHead(404, { 'Content-Type': 'text/plain' });
+        res.end("Not Found");
+        break;
+      }
+      routePattern = "/notify";
+      if(rejectIfRateLimited(req, res)) break;
+      handleMutation(routeNotifyHandler);
+      break;
+    case "telegram":
+      if(TELEGRAM_WEBHOOK_SECRET && args.length === 2 && args[1] === "webhook") {
+        routePattern = "/telegram/webhook";
+        handle(routeTelegramWebhookHandler);
+        break;
+      }
+      res.writeHead(404, { 'Content-Type': 'text/plain' });
+      res.end("Not Found");
+      break;
+    case "mark":
+      if(req.method === "POST") {
+        routePattern = args.length === 1 ? "/mark" : "/mark/{pkgname}";
+        if(rejectIfRateLimited(req, res)) break;
+        handleMutation(routeMarkHandler);
+        break;
+      }
+      routePattern = "/mark/{pkgname}/{mark}";
+      if(rejectIfRateLimited(req, res)) break;
+      handleMutation(routeUnmarkHandler);
       break;
     default:
       res.writeHead(404, { 'Content-Type': 'text/plain' });
@@ -1357,6 +3382,250 @@ const server = http.createServer((req, res) => {
   }
 });
 
-server.listen(30644);
+server.listen(30644, HTTP_HOST);
+
+/**
+ * @type {http.Server | null}
+ */
+let metricsServer = null;
+if(METRICS_PORT) {
+  metricsServer = http.createServer((req, res) => {
+    const url = new URL(req.url, `http://${req.headers.host}`);
+    if(url.pathname === "/metrics") {
+      routeMetricsHandler(req, res).catch(err => respondError(res, err));
+      return;
+    }
+    res.writeHead(404, { 'Content-Type': 'text/plain' });
+    res.end("Not Found");
+  });
+  metricsServer.listen(Number(METRICS_PORT), METRICS_HOST);
+}
+
+// reminders are off unless PLCT_STALE_REMINDER_DAYS is set
+const STALE_REMINDER_DAYS = Number(process.env["PLCT_STALE_REMINDER_DAYS"] || 0);
+const STALE_REMINDER_INTERVAL = Number(process.env["PLCT_STALE_REMINDER_INTERVAL"] || 3600) * 1000;
+// "22-8": no reminders from 22:00 to 7:59
+const STALE_REMINDER_QUIET_HOURS = parseQuietHours(process.env["PLCT_STALE_REMINDER_QUIET_HOURS"]);
+
+/**
+ * @description ping every packager once, listing all the packages they have held for STALE_REMINDER_DAYS
+ * since being assigned or last reminded
+ */
+async function remindStaleAssignments() {
+  if(isQuietHour(new Date(), STALE_REMINDER_QUIET_HOURS)) {
+    verb(remindStaleAssignments, "quiet hours, skipping");
+    return;
+  }
+  const staleUsers = findStaleAssignments(Date.now() - STALE_REMINDER_DAYS * 86400e3);
+  verb(remindStaleAssignments, "packagers to remind:", staleUsers.length);
+  if(staleUsers.length === 0) return;
+  for(const user of staleUsers) {
+    const link = getMentionLink(user.userid, null, getAlias(user.userid));
+    const pkgnames = user.packages.map(pkg => pkg.name);
+    // not waiting for the message, so that a slow send doesn't remind twice
+    user.packages.forEach(pkg => pkg.remindedAt = Date.now());
+    sendMessage(CHAT_ID, tgMessages.staleReminder({ link, pkgnames, days: STALE_REMINDER_DAYS }), {
+      parse_mode: "MarkdownV2",
+      disable_notification: false,
+    }, true).catch(err => verb(remindStaleAssignments, "failed to remind", user.userid, err));
+  }
+  await storePackageStatus();
+}
+
+/**
+ * @type {NodeJS.Timeout | null}
+ */
+let staleReminderTimer = null;
+if(STALE_REMINDER_DAYS > 0) {
+  staleReminderTimer = setInterval(() => {
+    remindStaleAssignments().catch(err => verb(remindStaleAssignments, "failed:", err));
+  }, STALE_REMINDER_INTERVAL);
+}
+
+const PENDING_NOTIFICATION_INTERVAL = Number(process.env["PLCT_PENDING_NOTIFICATION_INTERVAL"] || 300) * 1000;
+// a notification failing this many rounds is dropped, so that it can't hold up its chat forever
+const PENDING_NOTIFICATION_MAX_ATTEMPTS = 10;
+let isRetryingPendingNotifications = false;
+
+/**
+ * @description send the stored notifications again, oldest first. One failing for a transient reason is kept
+ * for the next round, and holds back the later ones of its chat to keep their order; one failing for another reason
+ * (e.g. the bot was kicked, or the thread deleted), or for the PENDING_NOTIFICATION_MAX_ATTEMPTS-th time, is dropped
+ */
+async function retryPendingNotifications() {
+  if(pendingNotifications.length === 0 || isRetryingPendingNotifications) return;
+  isRetryingPendingNotifications = true;
+  verb(retryPendingNotifications, "pending notifications:", pendingNotifications.length);
+  /**
+   * @type {Set<string>} chats whose notifications are held back until the next round
+   */
+  const failingChats = new Set();
+  try {
+    // notifications failing meanwhile are appended, and wait for the next round
+    for(const notification of pendingNotifications.slice()) {
+      const chatKey = String(notification.chatId);
+      if(failingChats.has(chatKey)) continue;
+      try {
+        await sendMessage(notification.chatId, notification.text, notification.options);
+      } catch(err) {
+        notification.attempts++;
+        if(isTransientSendError(err) && notification.attempts < PENDING_NOTIFICATION_MAX_ATTEMPTS) {
+          // telegram is probably still down, try again next time
+          verb(retryPendingNotifications, "still failing:", err.message);
+          failingChats.add(chatKey);
+          continue;
+        }
+        verb(retryPendingNotifications, "dropping the notification after", notification.attempts, "attempts:", err.message);
+      }
+      pendingNotifications.splice(pendingNotifications.indexOf(notification), 1);
+    }
+  } finally {
+    isRetryingPendingNotifications = false;
+    await storePendingNotifications();
+  }
+}
+
+const pendingNotificationTimer = setInterval(() => {
+  retryPendingNotifications().catch(err => verb(retryPendingNotifications, "failed:", err));
+}, PENDING_NOTIFICATION_INTERVAL);
+
+// auto-drop is off unless PLCT_AUTO_DROP_DAYS is set
+const AUTO_DROP_DAYS = Number(process.env["PLCT_AUTO_DROP_DAYS"] || 0);
+const AUTO_DROP_INTERVAL = Number(process.env["PLCT_AUTO_DROP_INTERVAL"] || 3600) * 1000;
+/**
+ * @type {import("./utils").HistoryActorInterface}
+ */
+const AUTO_DROP_ACTOR = { via: "auto", uid: null };
+
+/**
+ * @description release packages held for AUTO_DROP_DAYS and marked as stuck, unless also marked as noautodrop,
+ * the same way as /delete releases them, then clear the stuck mark so that the next packager starts afresh
+ */
+async function autoDropStuckAssignments() {
+  const cutoff = Date.now() - AUTO_DROP_DAYS * 86400e3;
+  const candidates = packageStatus.flatMap(user => user.packages
+    .filter(pkg => pkg.assignedAt < cutoff)
+    .filter(pkg => {
+      const marks = getPackageMarkNamesByPkgname(pkg.name);
+      return marks.includes("stuck") && !marks.includes("noautodrop");
+    })
+    .map(pkg => ({ userId: user.userid, pkgname: pkg.name, assignedAt: pkg.assignedAt })));
+  verb(autoDropStuckAssignments, "packages to drop:", candidates.length);
+  for(const { userId, pkgname, assignedAt } of candidates) {
+    await withDeleteLock(pkgname, async () => {
+      // a /delete or /drop may have got there first
+      if(localUtils.findUserIdByPackage(pkgname) !== userId) return;
+      const days = Math.floor((Date.now() - assignedAt) / 86400e3);
+      const stuckMark = packageMarks.find(pkg => pkg.name === pkgname).marks.find(mark => mark.name === "stuck");
+      const reason = tgMessages.autoDropReason({ days, comment: stuckMark.comment });
+      const success = await new Promise((resolve) => _merge(pkgname, userId, (success, failReason) => {
+        if(!success) verb(autoDropStuckAssignments, "failed to drop", pkgname, failReason);
+        resolve(success);
+      }, AUTO_DROP_ACTOR, reason));
+      if(!success) return;
+      await _unmark(pkgname, "stuck", () => {}, AUTO_DROP_ACTOR);
+      const link = getMentionLink(userId, null, getAlias(userId));
+      sendMessage(getDestination("merge"), tgMessages.autoDrop({ link, pkgname, reason }), {
+        parse_mode: "MarkdownV2",
+        disable_notification: false,
+      }, true).catch(err => verb(autoDropStuckAssignments, "failed to announce", pkgname, err));
+    });
+  }
+}
+
+/**
+ * @type {NodeJS.Timeout | null}
+ */
+let autoDropTimer = null;
+if(AUTO_DROP_DAYS > 0) {
+  autoDropTimer = setInterval(() => {
+    autoDropStuckAssignments().catch(err => verb(autoDropStuckAssignments, "failed:", err));
+  }, AUTO_DROP_INTERVAL);
+}
+
+// the weekly report is off unless PLCT_WEEKLY_REPORT_WEEKDAY is set, 0 (Sunday) to 6 (Saturday) in UTC+8
+const WEEKLY_REPORT_WEEKDAY = process.env["PLCT_WEEKLY_REPORT_WEEKDAY"] ? Number(process.env["PLCT_WEEKLY_REPORT_WEEKDAY"]) : null;
+const WEEKLY_REPORT_HOUR = Number(process.env["PLCT_WEEKLY_REPORT_HOUR"] || 10);
+const WEEKLY_REPORT_PIN = process.env["PLCT_WEEKLY_REPORT_PIN"] === "1";
+/**
+ * @type {number | null} day number of the last report, so that it's posted once within its hour
+ */
+let lastWeeklyReportDay = null;
+
+/**
+ * @description post the report of the past week on WEEKLY_REPORT_WEEKDAY, within WEEKLY_REPORT_HOUR
+ */
+async function postWeeklyReport() {
+  const now = Date.now();
+  const date = toLocalDate(now);
+  if(date.getUTCDay() !== WEEKLY_REPORT_WEEKDAY || date.getUTCHours() !== WEEKLY_REPORT_HOUR ||
+    lastWeeklyReportDay === getDayNumber(now)) return;
+  lastWeeklyReportDay = getDayNumber(now);
+  verb(postWeeklyReport, "posting the weekly report");
+  // part by part, since sendMessage() resolves with the last part of a long text, and the first one is pinned
+  const parts = splitLongText(renderWeeklyReport(now).text, 4000);
+  /**
+   * @type {TelegramBot.Message[]}
+   */
+  const messages = [];
+  for(const part of parts) {
+    messages.push(await sendMessage(CHAT_ID, part, { parse_mode: "MarkdownV2" }));
+  }
+  if(WEEKLY_REPORT_PIN) {
+    await pinMessage(CHAT_ID, messages[0].message_id);
+  }
+}
+
+/**
+ * @type {NodeJS.Timeout | null}
+ */
+let weeklyReportTimer = null;
+if(WEEKLY_REPORT_WEEKDAY !== null) {
+  weeklyReportTimer = setInterval(() => {
+    postWeeklyReport().catch(err => verb(postWeeklyReport, "failed:", err));
+  }, 60e3);
+}
+
+/**
+ * @description stop taking requests and updates, then wait for in-flight requests and queued messages
+ * (e.g. the "已出包" pings of a /delete) for at most SHUTDOWN_GRACE_PERIOD ms
+ */
+async function drain() {
+  verb(drain, "draining for at most", SHUTDOWN_GRACE_PERIOD, "ms");
+  server.close();
+  // event streams never finish on their own
+  eventStreams.forEach(stream => stream.end());
+  if(metricsServer) {
+    metricsServer.close();
+  }
+  if(staleReminderTimer) {
+    clearInterval(staleReminderTimer);
+  }
+  if(autoDropTimer) {
+    clearInterval(autoDropTimer);
+  }
+  if(weeklyReportTimer) {
+    clearInterval(weeklyReportTimer);
+  }
+  clearInterval(pendingNotificationTimer);
+  if(!TELEGRAM_WEBHOOK_SECRET) {
+    bot.stopPolling();
+  }
+  const deadline = Date.now() + SHUTDOWN_GRACE_PERIOD;
+  while(Date.now() < deadline) {
+    // throttled messages would otherwise be held back for 120 seconds
+    messageQueue.forEach(msg => {
+      if(msg.throttle) msg.timestamp = Date.now() - 120e3;
+    });
+    if(inFlightRequestCount === 0 && inFlightMessageCount === 0 && messageQueue.length === 0) {
+      verb(drain, "all requests and messages are done");
+      return;
+    }
+    await sleep(100);
+  }
+  verb(drain, "grace period exceeded with", inFlightRequestCount, "requests and",
+    messageQueue.length + inFlightMessageCount, "messages left");
+}
+drainBeforeExit = drain;
 
 })();  // end of the async IIFE wrapper
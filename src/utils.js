@@ -3,6 +3,7 @@
 
 const assert = require("assert");
 const crypto = require("crypto");
+const { EventEmitter } = require("events");
 const _equal = require("deep-equal");
 const fs = require("fs");
 const { readFile } = require("fs/promises");
@@ -11,6 +12,15 @@ const unlink = promisify(fs.unlink);
 const writeFile = promisify(fs.writeFile);
 
 const verb = require("./_verbose");
+const { getClientIp } = require("./_clientIp");
+const { splitLongText } = require("./_splitText");
+const { parseQuietHours, isQuietHour } = require("./_quietHours");
+const { withRetry } = require("./_withRetry");
+const { filterHistory } = require("./_history");
+const { isValidPkgname } = require("./_pkgname");
+const { toSafeMd, _safemd, toSafeCode, wrapCode, getMentionLink, getLogDirLinkMd } = require("./_markdown");
+const { TZ, getDayNumber, toLocalDate } = require("./_time");
+const { sortPackageStatus } = require("./_packageStatus");
 
 const BASE_LOG_DIR = process.env["PLCT_BASE_LOG_DIR"] || "";
 
@@ -18,6 +28,8 @@ const BASE_LOG_DIR = process.env["PLCT_BASE_LOG_DIR"] || "";
  * @typedef PackageInterface
  * @prop {string} name
  * @prop {number} lastActive
+ * @prop {number} assignedAt ms since epoch, when the current packager got the package
+ * @prop {number} [remindedAt] when the packager was last reminded of holding it for long
  */
 
 /**
@@ -40,6 +52,7 @@ let packageStatus = [];
         alias: string;
       } | null;
       comment: string;
+      markedAt: number;
     }[];
   }[] }
  */
@@ -49,6 +62,66 @@ loadPackageMarks();
 // In order to keep refs in plct-archrv-bot.js, this variable should never be assigned again.
 const packageMarks = _packageMarksForInit;
 
+/**
+ * @typedef HistoryActorInterface
+ * @prop {"bot" | "api" | "cli" | "auto"} via a bot command, the HTTP API, `src/manage.js import` or the auto-drop job
+ * @prop {number | null} uid tg uid of the user issuing the bot command, null otherwise
+ */
+
+/**
+ * @typedef HistoryInterface
+ * @prop {string} pkgname
+ * @prop {"add" | "drop" | "reassign" | "mark" | "unmark"} action
+ * @prop {HistoryActorInterface} actor
+ * @prop {string} detail mark name and comment for mark actions, old and new uid for reassign, empty otherwise
+ * @prop {number} time
+ */
+
+/**
+ * @typedef PendingNotificationInterface
+ * @prop {number | string} chatId
+ * @prop {string} text as sent, e.g. in MarkdownV2
+ * @prop {Record<string, any>} options of sendMessage()
+ * @prop {number} failedAt
+ * @prop {number} attempts failed retries since it was stored
+ */
+
+/**
+ * @typedef PackageMessageInterface
+ * @prop {string} pkgname
+ * @prop {string} chatId
+ * @prop {number | null} threadId forum topic, null outside forums
+ * @prop {number} messageId of the last ping about the package in this chat, replied to by later notifications
+ * @prop {number} sentAt
+ */
+
+/**
+ * @type {HistoryInterface[]}
+ */
+let _packageHistoryForInit;
+
+loadPackageHistory();
+// same as packageMarks, this variable should never be assigned again.
+const packageHistory = _packageHistoryForInit;
+
+/**
+ * @type {PendingNotificationInterface[]}
+ */
+let _pendingNotificationsForInit;
+
+loadPendingNotifications();
+// same as packageMarks, this variable should never be assigned again.
+const pendingNotifications = _pendingNotificationsForInit;
+
+/**
+ * @type {PackageMessageInterface[]}
+ */
+let _packageMessagesForInit;
+
+loadPackageMessages();
+// same as packageMarks, this variable should never be assigned again.
+const packageMessages = _packageMessagesForInit;
+
 /**
  * @type {Record<string, string>}
  */
@@ -61,6 +134,7 @@ const aliasMap = {};
       requireComment: boolean;
       allowUserModification: { mark: boolean; unmark: boolean; };
       appendTimeComment: boolean;
+      clearOnMerge: boolean;
       triggers: { name: string, op: "mark" | "unmark", when: "mark" | "unmark" }[];
     }>}
  */
@@ -71,6 +145,7 @@ const MARK_CONFIG = {
     requireComment: true,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: false,
+    clearOnMerge: true,
     triggers: [],
   },
   upstreamed: {
@@ -79,6 +154,7 @@ const MARK_CONFIG = {
     requireComment: true,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: false,
+    clearOnMerge: false,
     triggers: [],
   },
   outdated: {
@@ -87,6 +163,7 @@ const MARK_CONFIG = {
     requireComment: false,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: false, // must be false for auto-unmark by pkgname to work properly
+    clearOnMerge: true,
     triggers: [],
   },
   outdated_dep: {
@@ -95,6 +172,7 @@ const MARK_CONFIG = {
     requireComment: true,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: false, // must be false for auto-unmark by pkgname to work properly
+    clearOnMerge: true,
     triggers: [],
   },
   stuck: {
@@ -103,6 +181,7 @@ const MARK_CONFIG = {
     requireComment: true,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: false,
+    clearOnMerge: true,
     triggers: [],
   },
   noqemu: {
@@ -111,6 +190,7 @@ const MARK_CONFIG = {
     requireComment: false,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: false,
+    clearOnMerge: false,
     triggers: [],
   },
   ready: {
@@ -119,6 +199,7 @@ const MARK_CONFIG = {
     requireComment: false,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: true,
+    clearOnMerge: true,
     triggers: [
       { name: "failing",      op: "unmark", when: "mark" },
       { name: "flaky",        op: "unmark", when: "mark" },
@@ -130,6 +211,7 @@ const MARK_CONFIG = {
     requireComment: false,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: false,
+    clearOnMerge: true,
     triggers: [
       { name: "failing",      op: "unmark", when: "mark" },
       { name: "flaky",        op: "unmark", when: "mark" },
@@ -146,6 +228,7 @@ const MARK_CONFIG = {
     requireComment: true,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: false, // must be false for auto-unmark by pkgname to work properly
+    clearOnMerge: true,
     triggers: [],
   },
   flaky: {
@@ -154,6 +237,7 @@ const MARK_CONFIG = {
     requireComment: true,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: false,
+    clearOnMerge: false,
     triggers: [
       { name: "ready",        op: "unmark", when: "mark" },
     ],
@@ -164,6 +248,7 @@ const MARK_CONFIG = {
     requireComment: false,
     allowUserModification: { mark: false, unmark: false },
     appendTimeComment: true,
+    clearOnMerge: true,
     triggers: [
       { name: "ready",        op: "unmark", when: "mark" },
     ],
@@ -174,6 +259,16 @@ const MARK_CONFIG = {
     requireComment: true,
     allowUserModification: { mark: true, unmark: true },
     appendTimeComment: true,
+    clearOnMerge: false,
+    triggers: [],
+  },
+  noautodrop: {
+    desc: "不自动释放",
+    helpMsg: "即使长期标记为 stuck，也不要自动释放这个包的认领",
+    requireComment: false,
+    allowUserModification: { mark: true, unmark: true },
+    appendTimeComment: false,
+    clearOnMerge: true,
     triggers: [],
   }
 };
@@ -184,7 +279,16 @@ const MARK_CONFIG = {
 const MARK2STR = objectMap(MARK_CONFIG, v => v.desc);
 
 const MAX_SLEEP_TIME = 2147483647;  // to avoid TimeoutOverflowWarning
-const TZ = +8;  // UTC+8
+
+/**
+ * @description the lists of the upstream status page a package can leave, as accepted by /delete.
+ * The pings word them through `deleteStatuses` of every bundle in _messages.js
+ */
+const DELETE_STATUSES = /** @type {const} */ (["ftbfs", "leaf", "rotted"]);
+
+/**
+ * @typedef {typeof DELETE_STATUSES[number]} DeleteStatus
+ */
 
 
 /**
@@ -212,10 +316,18 @@ function sleep(ms) {
   return new Promise((res) => setTimeout(res, ms));
 }
 
+/**
+ * @param {string} name file name in db/
+ * @param {string} data
+ */
+function writeDbFile(name, data) {
+  return withRetry(() => writeFile(__dirname + "/../db/" + name, data), `writing ${name}`);
+}
+
 async function storePackageStatus() {
   verb(storePackageStatus);
-  await writeFile(__dirname + "/../db/packageStatus.json", JSON.stringify(packageStatus, null, 2));
-  await writeFile(__dirname + "/../db/packageStatus.bak.json", JSON.stringify(packageStatus, null, 2));
+  await writeDbFile("packageStatus.json", JSON.stringify(packageStatus, null, 2));
+  await writeDbFile("packageStatus.bak.json", JSON.stringify(packageStatus, null, 2));
 }
 
 function storePackageStatusSync() {
@@ -226,8 +338,8 @@ function storePackageStatusSync() {
 
 async function storePackageMarks() {
   verb(storePackageMarks);
-  await writeFile(__dirname + "/../db/packageMarks.json", JSON.stringify(packageMarks, null, 2));
-  await writeFile(__dirname + "/../db/packageMarks.bak.json", JSON.stringify(packageMarks, null, 2));
+  await writeDbFile("packageMarks.json", JSON.stringify(packageMarks, null, 2));
+  await writeDbFile("packageMarks.bak.json", JSON.stringify(packageMarks, null, 2));
 }
 
 function storePackageMarksSync() {
@@ -236,6 +348,123 @@ function storePackageMarksSync() {
   fs.writeFileSync(__dirname + "/../db/packageMarks.bak.json", JSON.stringify(packageMarks, null, 2));
 }
 
+/**
+ * @template T
+ * @param {Promise<T>} promise
+ * @param {number} ms
+ * @param {string} what used in the error message
+ * @returns {Promise<T>} rejects if `promise` is not settled within `ms`
+ */
+function withTimeout(promise, ms, what) {
+  /**
+   * @type {NodeJS.Timeout}
+   */
+  let timer;
+  const deadline = new Promise((_, reject) => {
+    timer = setTimeout(() => reject(new Error(`${what} timed out after ${ms}ms`)), ms);
+  });
+  return Promise.race([promise, deadline]).finally(() => clearTimeout(timer));
+}
+
+/**
+ * @description check that the db files are still readable and writable, used by the health check
+ * @param {number} timeout in ms
+ * @returns {Promise<void>} rejects if any db file is inaccessible or the check takes longer than `timeout`
+ */
+async function checkStorage(timeout) {
+  verb(checkStorage, timeout);
+  const files = ["packageStatus.json", "packageMarks.json"];
+  await withTimeout(Promise.all(files.map(file => {
+    return fs.promises.access(__dirname + "/../db/" + file, fs.constants.R_OK | fs.constants.W_OK);
+  })), timeout, "storage check");
+}
+
+async function storePackageHistory() {
+  verb(storePackageHistory);
+  await writeDbFile("packageHistory.json", JSON.stringify(packageHistory, null, 2));
+  await writeDbFile("packageHistory.bak.json", JSON.stringify(packageHistory, null, 2));
+}
+
+function storePackageHistorySync() {
+  verb(storePackageHistorySync);
+  fs.writeFileSync(__dirname + "/../db/packageHistory.json", JSON.stringify(packageHistory, null, 2));
+  fs.writeFileSync(__dirname + "/../db/packageHistory.bak.json", JSON.stringify(packageHistory, null, 2));
+}
+
+async function storePendingNotifications() {
+  verb(storePendingNotifications);
+  await writeDbFile("pendingNotifications.json", JSON.stringify(pendingNotifications, null, 2));
+  await writeDbFile("pendingNotifications.bak.json", JSON.stringify(pendingNotifications, null, 2));
+}
+
+async function storePackageMessages() {
+  verb(storePackageMessages);
+  await writeDbFile("packageMessages.json", JSON.stringify(packageMessages, null, 2));
+  await writeDbFile("packageMessages.bak.json", JSON.stringify(packageMessages, null, 2));
+}
+
+function storePackageMessagesSync() {
+  verb(storePackageMessagesSync);
+  fs.writeFileSync(__dirname + "/../db/packageMessages.json", JSON.stringify(packageMessages, null, 2));
+  fs.writeFileSync(__dirname + "/../db/packageMessages.bak.json", JSON.stringify(packageMessages, null, 2));
+}
+
+function storePendingNotificationsSync() {
+  verb(storePendingNotificationsSync);
+  fs.writeFileSync(__dirname + "/../db/pendingNotifications.json", JSON.stringify(pendingNotifications, null, 2));
+  fs.writeFileSync(__dirname + "/../db/pendingNotifications.bak.json", JSON.stringify(pendingNotifications, null, 2));
+}
+
+/**
+ * @description 记录一条历史，调用方需要在写入 status / marks 的同时调用 storePackageHistory()
+ * @param {string} pkgname
+ * @param {HistoryInterface["action"]} action
+ * @param {HistoryActorInterface} actor
+ * @param {string} [detail]
+ */
+function recordHistory(pkgname, action, actor, detail) {
+  verb(recordHistory, pkgname, action, actor, detail);
+  const entry = { pkgname, action, actor, detail: detail || "", time: Date.now() };
+  packageHistory.push(entry);
+  dataVersion++;
+  historyEvents.emit("record", entry);
+}
+
+/**
+ * @param {number} length
+ * @description forget the entries recorded after the history had `length` entries, when their changes are reverted
+ */
+function truncateHistory(length) {
+  verb(truncateHistory, length);
+  packageHistory.splice(length);
+  dataVersion++;
+}
+
+// bumped on every change of assignments, marks and aliases, i.e. of what /pkg shows
+let dataVersion = 0;
+
+function getDataVersion() {
+  return dataVersion;
+}
+
+/**
+ * @description emits "record" with the HistoryInterface entry on every change of assignments and marks
+ */
+const historyEvents = new EventEmitter();
+// one listener per /events client
+historyEvents.setMaxListeners(0);
+
+/**
+ * @param {string | null} pkgname null for all packages
+ * @param {number} limit
+ * @param {number} [since] only entries at or after this time (ms)
+ * @param {number} [until] only entries at or before this time (ms)
+ * @returns {HistoryInterface[]} newest first
+ */
+function getPackageHistory(pkgname, limit, since = 0, until = Infinity) {
+  return filterHistory(packageHistory, pkgname, limit, since, until);
+}
+
 function loadPackageStatus() {
   verb(loadPackageStatus);
   try {
@@ -272,10 +501,17 @@ function _updatePackageStatusSchema(oldPackageStatus) {
   if(oldPackageStatus.filter(({ packages }) => {
     // is packages already of PackageInterface[] type?
     return packages.length === 0 || packages.filter(p => typeof p === "string").length === 0;
-  }).length === 0) {
-    // was converted to new schema before
-    // @ts-ignore
-    return oldPackageStatus.slice();
+  }).length === oldPackageStatus.length) {
+    // was converted to new schema before, only assignedAt may be missing
+    return oldPackageStatus.map(user => ({
+      userid: user.userid,
+      username: user.username,
+      // @ts-ignore
+      packages: user.packages.map(pkg => Object.assign({}, pkg, {
+        // lastActive has been set on claiming and never updated since, so it is the time of assignment
+        assignedAt: typeof pkg.assignedAt === "number" ? pkg.assignedAt : pkg.lastActive,
+      })),
+    }));
   }
   return oldPackageStatus.map(user => {
     /**
@@ -292,7 +528,7 @@ function _updatePackageStatusSchema(oldPackageStatus) {
         if(typeof pkg !== "string") {
           throw new Error("Unexpected package type");
         }
-        return { name: pkg, lastActive: Date.now() };
+        return { name: pkg, lastActive: Date.now(), assignedAt: Date.now() };
       }),
     };
     return ret;
@@ -319,6 +555,85 @@ function loadPackageMarks() {
   }
 }
 
+function loadPackageHistory() {
+  verb(loadPackageHistory);
+  if(typeof _packageHistoryForInit !== "undefined") {
+    verb("ERROR: packageHistory is already loaded");
+    return;
+  }
+  try {
+    _packageHistoryForInit = require("../db/packageHistory.json");
+  } catch(e) {
+    verb(loadPackageHistory, e);
+    try {
+      _packageHistoryForInit = require("../db/packageHistory.bak.json");
+    } catch(e) {
+      verb(loadPackageHistory, e);
+      // packageHistory is not initialized yet, the file will be written on the first change
+      _packageHistoryForInit = [];
+    }
+  }
+}
+
+function loadPackageMessages() {
+  verb(loadPackageMessages);
+  try {
+    _packageMessagesForInit = require("../db/packageMessages.json");
+  } catch(e) {
+    verb(loadPackageMessages, e);
+    try {
+      _packageMessagesForInit = require("../db/packageMessages.bak.json");
+    } catch(e) {
+      verb(loadPackageMessages, e);
+      // nothing has been pinged so far
+      _packageMessagesForInit = [];
+    }
+  }
+}
+
+/**
+ * @param {string} pkgname
+ * @param {number | string} chatId
+ * @param {number | null} threadId
+ * @returns {number | null} the message_id of the last ping about `pkgname` in this chat and topic
+ */
+function findPackageMessageId(pkgname, chatId, threadId) {
+  const found = packageMessages.find(msg => msg.pkgname === pkgname && msg.chatId === String(chatId) && msg.threadId === threadId);
+  return found ? found.messageId : null;
+}
+
+/**
+ * @param {string} pkgname
+ * @param {number | string} chatId
+ * @param {number | null} threadId
+ * @param {number} messageId replaces the one stored for `pkgname` in this chat and topic
+ */
+function setPackageMessageId(pkgname, chatId, threadId, messageId) {
+  const found = packageMessages.find(msg => msg.pkgname === pkgname && msg.chatId === String(chatId) && msg.threadId === threadId);
+  if(found) {
+    found.messageId = messageId;
+    found.sentAt = Date.now();
+  } else {
+    packageMessages.push({ pkgname, chatId: String(chatId), threadId, messageId, sentAt: Date.now() });
+  }
+}
+
+function loadPendingNotifications() {
+  verb(loadPendingNotifications);
+  try {
+    _pendingNotificationsForInit = require("../db/pendingNotifications.json");
+  } catch(e) {
+    verb(loadPendingNotifications, e);
+    try {
+      _pendingNotificationsForInit = require("../db/pendingNotifications.bak.json");
+    } catch(e) {
+      verb(loadPendingNotifications, e);
+      // nothing has failed so far
+      _pendingNotificationsForInit = [];
+    }
+  }
+}
+
 /**
  * @param {{
     name: string;
@@ -330,6 +645,7 @@ function loadPackageMarks() {
         alias: string;
       } | null;
       comment: string;
+      markedAt?: number;
     }[];
   }[]} oldPackageMarks
  */
@@ -344,19 +660,26 @@ function _updatePackageMarkSchema(oldPackageMarks) {
           alias: string;
         } | null;
         comment: string;
+        markedAt: number;
       }[]; }}
      */
     const ret = { name: oldPackageMark.name, marks: [] };
     const marks = oldPackageMark.marks;
+    // marks made before markedAt was recorded count from now
+    const now = Date.now();
     for(const mark of marks) {
       if(typeof mark === "string") {
-        ret.marks.push({ name: mark, by: null, comment: "" });
-      } else if(typeof mark.comment !== "string") {
+        ret.marks.push({ name: mark, by: null, comment: "", markedAt: now });
+        continue;
+      }
+      if(typeof mark.comment !== "string") {
         mark.comment = "";
-        ret.marks.push(mark);
-      } else {
-        ret.marks.push(mark);
       }
+      if(typeof mark.markedAt !== "number") {
+        mark.markedAt = now;
+      }
+      // @ts-ignore markedAt is set above
+      ret.marks.push(mark);
     }
     return ret;
   });
@@ -375,12 +698,25 @@ async function loadAlias() {
         aliasMap[uid] = _aliasMap[uid];
       }
     }
+    dataVersion++;
   } catch(e) {
     verb(loadAlias, e.message);
   }
 }
 loadAlias();
 
+/**
+ * @param {number} uid
+ * @param {string} alias
+ * @description set the alias of `uid` and write it back to config/alias.json
+ */
+async function setAlias(uid, alias) {
+  verb(setAlias, uid, alias);
+  aliasMap[uid] = alias;
+  dataVersion++;
+  await writeFile(__dirname + "/../config/alias.json", JSON.stringify(aliasMap, null, 2));
+}
+
 /**
  * @param {string} markName
  */
@@ -395,6 +731,13 @@ function getAvailableMarks() {
   return Object.keys(MARK_CONFIG);
 }
 
+/**
+ * @description marks that are cleared once the package is merged (via /delete)
+ */
+function getClearOnMergeMarks() {
+  return getAvailableMarks().filter(mark => MARK_CONFIG[mark].clearOnMerge);
+}
+
 /**
  * @param {number} uid
  */
@@ -422,6 +765,45 @@ function getUserIdByAlias(alias) {
   else return 0;
 }
 
+/**
+ * @description like getUserIdByAlias, but returns null instead of falling back to the bot id.
+ * Aliases are matched case-insensitively, an exact match wins.
+ * @param {string} alias
+ * @returns {number | null}
+ */
+function findUserIdByAlias(alias) {
+  for(const uid in aliasMap) {
+    if(aliasMap[uid] === alias) {
+      return Number(uid);
+    }
+  }
+  const aliasLower = alias.toLowerCase();
+  for(const uid in aliasMap) {
+    if(aliasMap[uid].toLowerCase() === aliasLower) {
+      return Number(uid);
+    }
+  }
+  return null;
+}
+
+/**
+ * @param {string} packager tg uid or alias
+ * @returns {number | null} null if nobody with this uid or alias is known, i.e. has an alias or packages
+ */
+function findUserIdByPackager(packager) {
+  const userId = /^\d+$/.test(packager) ? Number(packager) : findUserIdByAlias(packager);
+  if(userId === null) return null;
+  if(!hasAlias(userId) && !packageStatus.some(user => user.userid === userId)) return null;
+  return userId;
+}
+
+/**
+ * @param {number} uid
+ */
+function hasAlias(uid) {
+  return typeof aliasMap[uid] === "string";
+}
+
 /**
  * @param {string} pkgname
  * @returns {number | null}
@@ -474,6 +856,31 @@ function findPackageMarksByComment(comment) {
   }));
 }
 
+/**
+ * @param {string} mark
+ * @param {number} cutoff packages whose `mark` was made before this timestamp (ms) are stale
+ */
+function findStalePackageMarks(mark, cutoff) {
+  // prune empty entries. Not doing real work, thus we can save it later
+  inplaceFilter(packageMarks, pkg => pkg.marks.length > 0);
+
+  return packageMarks.filter(pkg => pkg.marks.some(markObj => markObj.name === mark && markObj.markedAt < cutoff));
+}
+
+/**
+ * @param {number} cutoff packages assigned, and last reminded of, before this timestamp (ms) are stale
+ * @returns {{ userid: number; packages: PackageInterface[]; }[]} packagers holding stale packages,
+ * the packages are the same objects as in packageStatus
+ */
+function findStaleAssignments(cutoff) {
+  return packageStatus
+    .map(user => ({
+      userid: user.userid,
+      packages: user.packages.filter(pkg => Math.max(pkg.assignedAt, pkg.remindedAt || 0) < cutoff),
+    }))
+    .filter(user => user.packages.length > 0);
+}
+
 /**
  * @param {string[]} markNames
  * @param {string} comment
@@ -513,43 +920,12 @@ function getMsgLink(options) {
   return `${msgLinkBase}/${options.msgId}`;
 }
 
-/**
- * @param {string | number} uid
- * @param {string} [username]
- * @param {string} [firstName]
- * @param {string} [lastName]
- * @param {boolean} [tag]
- */
-function getMentionLink(uid, username, firstName = "", lastName = "", tag = false) {
-  let userDisplayName = "@" + username;
-  if(!username) {
-    // see https://core.telegram.org/bots/api#markdownv2-style
-    userDisplayName = toSafeMd(`${firstName} ${lastName}`);
-    userDisplayName = userDisplayName.trimEnd();
-  } else {
-    userDisplayName = toSafeMd(userDisplayName);
-  }
-  if(tag) {
-    return `[${userDisplayName}\u200B](tg://user?id=${uid})`;
-  }
-  return `[${userDisplayName}](tg://user?id=${uid})`;
-}
-
-/**
- * @param {string} pkgname
- */
-function getErrorLogDirLink(pkgname) {
-  return BASE_LOG_DIR.replace("{pkgname}", pkgname);
-}
-
 /**
  * @param {string} pkgname
  * @param {string} unsafeMdText
  */
 function getErrorLogDirLinkMd(pkgname, unsafeMdText) {
-  const safeMdText = toSafeMd(unsafeMdText);
-  if(!BASE_LOG_DIR) return safeMdText;
-  return `[${safeMdText}](${getErrorLogDirLink(pkgname)})`;
+  return getLogDirLinkMd(BASE_LOG_DIR, pkgname, unsafeMdText);
 }
 
 /**
@@ -572,51 +948,6 @@ function getArrayXYSize(arr) {
   return [sizeX, sizeY];
 }
 
-/**
- * @param {string | number} unsafeMd unsafe markdown v2 text
- */
-function toSafeMd(unsafeMd) {
-  unsafeMd = String(unsafeMd);
-  // see https://core.telegram.org/bots/api#markdownv2-style
-  // eslint-disable-next-line no-useless-escape
-  return unsafeMd.replace(/([[\]()_*~`>#+\-=|{}\.!\\])/g, "\\$1");
-}
-
-/**
- * @param {TemplateStringsArray} unsafeMdArr unsafe markdown v2 texts
- * @param {any[]} safeMdArr
- * @description Use this with template strings. ```_safemd`unsafe ${"safe"} unsafe2}` ```
- */
-function _safemd(unsafeMdArr, ...safeMdArr) {
-  safeMdArr = safeMdArr.map(safeMd => String(safeMd));
-  // see https://core.telegram.org/bots/api#markdownv2-style
-  // eslint-disable-next-line no-useless-escape
-  const escapedMdArr = unsafeMdArr.map(unsafeMd => unsafeMd.replace(/([[\]()_*~`>#+\-=|{}\.!\\])/g, "\\$1"));
-  let result = "";
-  for(let i = 0; i < safeMdArr.length; i++) {
-    result += escapedMdArr[i];
-    result += safeMdArr[i];
-  }
-  result += escapedMdArr[escapedMdArr.length - 1];
-  return result;
-}
-
-/**
- * @param {string} unsafeCode unsafe markdown v2 code
- * @returns {string} safe code without backquotes
- */
-function toSafeCode(unsafeCode) {
-  return unsafeCode.replace(/([`\\])/g, "\\$1");
-}
-
-/**
- * @param {string} unsafeCode unsafe markdown v2 code
- * @returns {string} safe code with backquotes
- */
-function wrapCode(unsafeCode) {
-  return `\`${toSafeCode(unsafeCode)}\``;
-}
-
 /**
  * @param {number} bytes
  */
@@ -766,7 +1097,6 @@ function sha512hex(text) {
   return hashResult.digest("hex");
 }
 
-
 /**
  * @description 注意！keywords 不会被 escape，不要有特殊字符
  * @param {string[]} keywords
@@ -791,19 +1121,10 @@ function fullKwd2regexp(keywords, flags = "i") {
 
 /**
  * @param {{ userid: number; username: string | undefined; packages: PackageInterface[]; }[]} status
+ * @param {"oldest" | "newest"} [sort] see sortPackageStatus()
  */
-function stripPackageStatus(status) {
-  /**
-   * @type {{ alias: string; packages: string[]; }[]}
-   */
-  const ret = [];
-  for(const user of status) {
-    ret.push({
-      alias: getAlias(user.userid),
-      packages: user.packages.map(pkg => pkg.name),
-    });
-  }
-  return ret;
+function stripPackageStatus(status, sort = "oldest") {
+  return sortPackageStatus(status, sort, getAlias, Date.now());
 }
 
 /**
@@ -817,6 +1138,7 @@ function stripPackageStatus(status) {
         alias: string;
       } | null;
       comment: string;
+      markedAt: number;
     }[];
   }[] } status
  */
@@ -830,6 +1152,7 @@ function stripPackageMarks(status) {
         alias: string;
       };
       comment: string;
+      markedAt: number;
     }[];
   }[] }
    */
@@ -844,6 +1167,7 @@ function stripPackageMarks(status) {
             alias: mark.by ? mark.by.alias : "null"
           },
           comment: mark.comment,
+          markedAt: mark.markedAt,
         };
         return strippedMark;
       }),
@@ -939,9 +1263,12 @@ function inplaceFilter(arr, cond) {
 }
 
 module.exports = {
+  DELETE_STATUSES,
   MARK2STR,
   packageStatus,
   packageMarks,
+  packageHistory,
+  truncateHistory,
   defer,
   equal,
   _safemd,
@@ -955,26 +1282,53 @@ module.exports = {
   findPackageMarksByComment,
   findPackageMarksByMarkName,
   findPackageMarksByMarkNamesAndComment,
+  findStalePackageMarks,
+  findStaleAssignments,
   forceResplitLines,
   loadAlias,
+  setAlias,
   storePackageStatus,
   storePackageStatusSync,
   storePackageMarks,
   storePackageMarksSync,
+  storePackageHistory,
+  storePackageHistorySync,
+  pendingNotifications,
+  storePendingNotifications,
+  storePendingNotificationsSync,
+  packageMessages,
+  storePackageMessages,
+  storePackageMessagesSync,
+  findPackageMessageId,
+  setPackageMessageId,
+  recordHistory,
+  historyEvents,
+  getDataVersion,
+  getPackageHistory,
+  withTimeout,
+  checkStorage,
   getTodayTimestamp,
   getCurrentTimeStr,
+  parseQuietHours,
+  isQuietHour,
   getMarkConfig,
   getAvailableMarks,
+  getClearOnMergeMarks,
   getAlias,
   getUserIdByAlias,
+  findUserIdByAlias,
+  findUserIdByPackager,
+  hasAlias,
   getMsgLink,
   getMentionLink,
   getErrorLogDirLinkMd,
   getArrayXYSize,
   sha512hex,
+  getClientIp,
   toSafeMd,
   toSafeCode,
   wrapCode,
+  splitLongText,
   readableFileSize,
   cleanup,
   sleep,
@@ -983,6 +1337,9 @@ module.exports = {
   kwd2regexp,
   fullKwd2regexp,
   escapeRegExp,
+  isValidPkgname,
   stripPackageStatus,
+  getDayNumber,
+  toLocalDate,
   stripPackageMarks,
 };
@@ -0,0 +1,81 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { assignPackage, movePackage, shouldPingOwner } = require("../src/_assign");
+
+/**
+ * @returns {import("../src/_assign").PackagerInterface[]}
+ */
+function makeStatus() {
+  return [
+    { userid: 1, username: "alice", packages: [
+      { name: "foo", lastActive: 100, assignedAt: 100 },
+      { name: "bar", lastActive: 200, assignedAt: 150 },
+    ] },
+    { userid: 2, username: undefined, packages: [{ name: "baz", lastActive: 300, assignedAt: 300 }] },
+  ];
+}
+
+test("claiming a free package assigns it", () => {
+  const status = makeStatus();
+  assert.strictEqual(assignPackage(status, "qux", 2, undefined, 1000), null);
+  assert.deepStrictEqual(status[1].packages.map(pkg => pkg.name), ["baz", "qux"]);
+  assert.strictEqual(assignPackage(status, "quux", 3, "carol", 1000), null);
+  assert.deepStrictEqual(status[2], { userid: 3, username: "carol", packages: [{ name: "quux", lastActive: 1000, assignedAt: 1000 }] });
+});
+
+test("claiming a package held by someone else is refused, naming the packager", () => {
+  const status = makeStatus();
+  assert.strictEqual(assignPackage(status, "foo", 2, undefined, 1000), 1);
+  assert.deepStrictEqual(status, makeStatus());
+});
+
+test("claiming a package again is a no-op", () => {
+  const status = makeStatus();
+  assert.strictEqual(assignPackage(status, "foo", 1, "alice", 1000), 1);
+  assert.deepStrictEqual(status, makeStatus());
+});
+
+test("reassigning moves the package to the new packager and restarts its clock", () => {
+  const status = makeStatus();
+  const marks = [{ name: "bar", marks: [{ name: "stuck", by: null, comment: "waiting upstream", markedAt: 120 }] }];
+  const savedMarks = structuredClone(marks);
+  movePackage(status, "bar", 2, 1000);
+  assert.deepStrictEqual(status[0].packages.map(pkg => pkg.name), ["foo"]);
+  assert.deepStrictEqual(status[1].packages, [
+    { name: "baz", lastActive: 300, assignedAt: 300 },
+    { name: "bar", lastActive: 1000, assignedAt: 1000 },
+  ]);
+  // marks are kept by pkgname, so they follow the package as they are
+  assert.deepStrictEqual(marks, savedMarks);
+});
+
+test("reassigning to someone without packages adds them", () => {
+  const status = makeStatus();
+  movePackage(status, "foo", 3, 1000);
+  assert.deepStrictEqual(status[2], { userid: 3, username: undefined, packages: [{ name: "foo", lastActive: 1000, assignedAt: 1000 }] });
+});
+
+test("undoing a reassign restores the status as it was", () => {
+  for(const newUserId of [2, 3]) {
+    const status = makeStatus();
+    const undo = movePackage(status, "foo", newUserId, 1000);
+    undo();
+    assert.deepStrictEqual(status, makeStatus());
+  }
+});
+
+test("the owner is pinged when someone else claims their package", () => {
+  const status = makeStatus();
+  const ownerId = assignPackage(status, "foo", 2, undefined, 400);
+  assert.strictEqual(shouldPingOwner(ownerId, 2, true), true);
+  assert.strictEqual(shouldPingOwner(ownerId, 2, false), false);
+});
+
+test("nobody is pinged for a repeated claim or a free package", () => {
+  const status = makeStatus();
+  assert.strictEqual(shouldPingOwner(assignPackage(status, "foo", 1, "alice", 400), 1, true), false);
+  assert.strictEqual(shouldPingOwner(assignPackage(status, "qux", 2, undefined, 400), 2, true), false);
+});
@@ -0,0 +1,39 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { getClientIp } = require("../src/_clientIp");
+
+const TRUSTED_PROXIES = ["127.0.0.1", "::1"];
+
+test("without X-Forwarded-For, the socket address is the client", () => {
+  assert.strictEqual(getClientIp("203.0.113.7", undefined, true, TRUSTED_PROXIES), "203.0.113.7");
+  assert.strictEqual(getClientIp("::ffff:203.0.113.7", undefined, true, TRUSTED_PROXIES), "203.0.113.7");
+});
+
+test("X-Forwarded-For is ignored when PLCT_TRUST_PROXY is off", () => {
+  assert.strictEqual(getClientIp("127.0.0.1", "198.51.100.1", false, TRUSTED_PROXIES), "127.0.0.1");
+  assert.strictEqual(getClientIp("203.0.113.7", "198.51.100.1, 10.0.0.1", false, TRUSTED_PROXIES), "203.0.113.7");
+});
+
+test("X-Forwarded-For of untrusted peers is ignored", () => {
+  assert.strictEqual(getClientIp("203.0.113.7", "198.51.100.1", true, TRUSTED_PROXIES), "203.0.113.7");
+});
+
+test("multi-hop X-Forwarded-For is walked from the right, skipping trusted proxies", () => {
+  // client, forged by the client, then two chained proxies
+  const forwardedFor = "192.0.2.66, 198.51.100.1, 10.0.0.2";
+  assert.strictEqual(getClientIp("127.0.0.1", forwardedFor, true, TRUSTED_PROXIES), "10.0.0.2");
+  assert.strictEqual(getClientIp("127.0.0.1", forwardedFor, true, [...TRUSTED_PROXIES, "10.0.0.2"]), "198.51.100.1");
+});
+
+test("repeated X-Forwarded-For headers are joined in order", () => {
+  const forwardedFor = ["198.51.100.1", "::ffff:10.0.0.2"];
+  assert.strictEqual(getClientIp("::1", forwardedFor, true, [...TRUSTED_PROXIES, "10.0.0.2"]), "198.51.100.1");
+});
+
+test("the leftmost hop is the client when every hop is a trusted proxy", () => {
+  assert.strictEqual(getClientIp("127.0.0.1", "198.51.100.1, 127.0.0.1", true, TRUSTED_PROXIES), "198.51.100.1");
+  assert.strictEqual(getClientIp("127.0.0.1", " , ", true, TRUSTED_PROXIES), "127.0.0.1");
+});
@@ -0,0 +1,47 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { filterHistory } = require("../src/_history");
+
+/**
+ * @param {string} pkgname
+ * @param {number} time
+ * @returns {import("../src/utils").HistoryInterface}
+ */
+function entry(pkgname, time) {
+  return { pkgname, action: "add", actor: { via: "api", uid: null }, detail: "", time };
+}
+
+const history = [entry("foo", 10), entry("bar", 20), entry("foo", 30), entry("bar", 40), entry("foo", 50)];
+
+/**
+ * @param {import("../src/utils").HistoryInterface[]} entries
+ */
+function times(entries) {
+  return entries.map(entry => entry.time);
+}
+
+test("without bounds, the newest entries come first up to the limit", () => {
+  assert.deepStrictEqual(times(filterHistory(history, null, 10)), [50, 40, 30, 20, 10]);
+  assert.deepStrictEqual(times(filterHistory(history, null, 2)), [50, 40]);
+  assert.deepStrictEqual(times(filterHistory(history, "foo", 10)), [50, 30, 10]);
+});
+
+test("since keeps entries at or after it", () => {
+  assert.deepStrictEqual(times(filterHistory(history, null, 10, 30)), [50, 40, 30]);
+  assert.deepStrictEqual(times(filterHistory(history, "bar", 10, 30)), [40]);
+});
+
+test("until keeps entries at or before it", () => {
+  assert.deepStrictEqual(times(filterHistory(history, null, 10, undefined, 30)), [30, 20, 10]);
+  // the limit counts the kept entries, not the skipped newer ones
+  assert.deepStrictEqual(times(filterHistory(history, null, 2, undefined, 30)), [30, 20]);
+});
+
+test("both bounds keep the entries in between", () => {
+  assert.deepStrictEqual(times(filterHistory(history, null, 10, 20, 40)), [40, 30, 20]);
+  assert.deepStrictEqual(times(filterHistory(history, "foo", 10, 20, 40)), [30]);
+  assert.deepStrictEqual(filterHistory(history, null, 10, 41, 49), []);
+});
@@ -0,0 +1,83 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { IdempotencyCache } = require("../src/_idempotency");
+
+/**
+ * @param {number} [maxKeys]
+ */
+function cacheAt(maxKeys = 1000) {
+  const clock = { now: 1000e3 };
+  /** @type {IdempotencyCache<{ status: number; body: string; }>} */
+  const cache = new IdempotencyCache(60e3, maxKeys, () => clock.now);
+  return { clock, cache };
+}
+
+/**
+ * @description a /delete: pings the group, then responds
+ * @param {string[]} sent
+ * @param {number} [status]
+ */
+function deletion(sent, status = 200) {
+  return async () => {
+    sent.push("(auto-merge) ping alice: foo 已出包");
+    return status === 200 ? { status, body: "success" } : null;
+  };
+}
+
+test("the same key replays the stored response without sending again", async () => {
+  const { cache } = cacheAt();
+  /** @type {string[]} */
+  const sent = [];
+  assert.deepStrictEqual(await cache.run("DELETE /delete/foo/ftbfs k1", deletion(sent)), { replayed: false, response: { status: 200, body: "success" } });
+  assert.deepStrictEqual(await cache.run("DELETE /delete/foo/ftbfs k1", deletion(sent)), { replayed: true, response: { status: 200, body: "success" } });
+  assert.strictEqual(sent.length, 1);
+});
+
+test("a duplicate arriving while the first is running waits for its response", async () => {
+  const { cache } = cacheAt();
+  /** @type {string[]} */
+  const sent = [];
+  const [first, second] = await Promise.all([
+    cache.run("k1", deletion(sent)),
+    cache.run("k1", deletion(sent)),
+  ]);
+  assert.strictEqual(first.replayed, false);
+  assert.deepStrictEqual(second, { replayed: true, response: { status: 200, body: "success" } });
+  assert.strictEqual(sent.length, 1);
+});
+
+test("other keys run on their own", async () => {
+  const { cache } = cacheAt();
+  /** @type {string[]} */
+  const sent = [];
+  await cache.run("k1", deletion(sent));
+  assert.strictEqual((await cache.run("k2", deletion(sent))).replayed, false);
+  assert.strictEqual(sent.length, 2);
+});
+
+test("failures aren't stored, so the key can be retried", async () => {
+  const { cache } = cacheAt();
+  /** @type {string[]} */
+  const sent = [];
+  assert.deepStrictEqual(await cache.run("k1", deletion(sent, 500)), { replayed: false, response: null });
+  await assert.rejects(cache.run("k1", async () => { throw new Error("store failed"); }), { message: "store failed" });
+  assert.strictEqual((await cache.run("k1", deletion(sent))).replayed, false);
+  assert.strictEqual((await cache.run("k1", deletion(sent))).replayed, true);
+  assert.strictEqual(sent.length, 2);
+});
+
+test("keys are forgotten after the ttl, or the oldest ones beyond maxKeys", async () => {
+  const { clock, cache } = cacheAt(2);
+  /** @type {string[]} */
+  const sent = [];
+  await cache.run("k1", deletion(sent));
+  clock.now += 60e3;
+  assert.strictEqual((await cache.run("k1", deletion(sent))).replayed, false);
+  await cache.run("k2", deletion(sent));
+  await cache.run("k3", deletion(sent));
+  assert.deepStrictEqual([...cache.responses.keys()], ["k2", "k3"]);
+  assert.strictEqual(sent.length, 4);
+});
@@ -0,0 +1,61 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { KeyedLock } = require("../src/_keyedLock");
+
+const tick = () => new Promise(res => setImmediate(res));
+
+test("two concurrent deletions of a package run one after the other, and only the first pings", async () => {
+  const lock = new KeyedLock();
+  const assigned = new Set(["foo"]);
+  /** @type {string[]} */
+  const pings = [];
+  /** @type {string[]} */
+  const steps = [];
+  const deletion = (/** @type {string} */ name) => lock.run("foo", async () => {
+    steps.push(`${name} start`);
+    if(!assigned.has("foo")) {
+      steps.push(`${name} end`);
+      return "already deleted";
+    }
+    // e.g. waiting for the stores and the ping
+    await tick();
+    pings.push("foo 已出包");
+    assigned.delete("foo");
+    steps.push(`${name} end`);
+    return "success";
+  });
+  assert.deepStrictEqual(await Promise.all([deletion("a"), deletion("b")]), ["success", "already deleted"]);
+  assert.deepStrictEqual(pings, ["foo 已出包"]);
+  assert.deepStrictEqual(steps, ["a start", "a end", "b start", "b end"]);
+});
+
+test("a failing run doesn't block the next one", async () => {
+  const lock = new KeyedLock();
+  const first = lock.run("foo", async () => { throw new Error("store failed"); });
+  const second = lock.run("foo", async () => "success");
+  await assert.rejects(first, { message: "store failed" });
+  assert.strictEqual(await second, "success");
+});
+
+test("runs on other keys don't wait", async () => {
+  const lock = new KeyedLock();
+  /** @type {string[]} */
+  const steps = [];
+  /** @type {() => void} */
+  let release = () => {};
+  const foo = lock.run("foo", () => new Promise(res => { release = () => res(steps.push("foo")); }));
+  await lock.run("bar", async () => steps.push("bar"));
+  release();
+  await foo;
+  assert.deepStrictEqual(steps, ["bar", "foo"]);
+});
+
+test("keys are forgotten once their last run is done", async () => {
+  const lock = new KeyedLock();
+  await lock.run("foo", async () => {});
+  await tick();
+  assert.strictEqual(lock.tails.size, 0);
+});
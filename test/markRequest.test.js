@@ -0,0 +1,87 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { HttpError } = require("../src/_httpError");
+const { getMarkItems, planMarks } = require("../src/_markRequest");
+
+/**
+ * @param {Partial<import("../src/_markRequest").MarkConfigInterface>} config
+ * @returns {import("../src/_markRequest").MarkConfigInterface}
+ */
+function markConfig(config) {
+  return {
+    desc: "",
+    helpMsg: "",
+    requireComment: false,
+    allowUserModification: { mark: true, unmark: true },
+    appendTimeComment: false,
+    clearOnMerge: false,
+    triggers: [],
+    ...config,
+  };
+}
+
+/**
+ * @type {Record<string, import("../src/_markRequest").MarkConfigInterface>}
+ */
+const MARK_CONFIG = {
+  outdated: markConfig({}),
+  stuck: markConfig({ requireComment: true }),
+  ready: markConfig({ appendTimeComment: true }),
+  failing: markConfig({ allowUserModification: { mark: false, unmark: false }, appendTimeComment: true }),
+};
+
+/**
+ * @param {string} mark
+ */
+function getMarkConfig(mark) {
+  if(!MARK_CONFIG[mark]) throw new HttpError(400, `unknown mark ${mark}`);
+  return MARK_CONFIG[mark];
+}
+
+/**
+ * @param {any} body
+ * @param {{ name: string; comment: string; }[]} [existingMarks]
+ */
+function plan(body, existingMarks = []) {
+  return planMarks(getMarkItems(body), existingMarks, getMarkConfig, "2024/1/1 08:00:00 (UTC+8)")
+    .map(({ mark, comment }) => ({ mark, comment }));
+}
+
+test("the single-object form sets its mark", () => {
+  assert.deepStrictEqual(plan({ token: "t", mark: "stuck", comment: " waiting upstream " }), [{ mark: "stuck", comment: "waiting upstream" }]);
+  assert.deepStrictEqual(plan({ mark: "ready" }), [{ mark: "ready", comment: "2024/1/1 08:00:00 (UTC+8)" }]);
+});
+
+test("the array form sets every mark", () => {
+  assert.deepStrictEqual(plan([{ mark: "outdated" }, { mark: "stuck", comment: "upstream" }]),
+    [{ mark: "outdated", comment: "" }, { mark: "stuck", comment: "upstream" }]);
+});
+
+test("marks not allowed by hand are refused in the single-object form", () => {
+  assert.throws(() => plan({ mark: "failing" }), { status: 403, message: "Forbidden: mark failing can't be set by hand" });
+  // even when it's already set
+  assert.throws(() => plan({ mark: "failing" }, [{ name: "failing", comment: "" }]), { status: 403 });
+});
+
+test("a mark not allowed by hand refuses the whole array", () => {
+  assert.throws(() => plan([{ mark: "outdated" }, { mark: "failing" }]), { status: 403 });
+});
+
+test("marks already set with the same comment, or without one, are skipped", () => {
+  const existing = [{ name: "stuck", comment: "upstream" }];
+  assert.deepStrictEqual(plan({ mark: "stuck" }, existing), []);
+  assert.deepStrictEqual(plan({ mark: "stuck", comment: "upstream" }, existing), []);
+  assert.deepStrictEqual(plan({ mark: "stuck", comment: "fixed upstream" }, existing), [{ mark: "stuck", comment: "fixed upstream" }]);
+});
+
+test("unknown marks, missing comments and malformed items are refused with 400", () => {
+  assert.throws(() => plan({ mark: "nope" }), { status: 400 });
+  assert.throws(() => plan([{ mark: "outdated" }, { mark: "stuck" }]), { status: 400, message: "Bad Request: mark stuck requires a comment" });
+  assert.throws(() => plan([]), { status: 400 });
+  assert.throws(() => plan([{ mark: "outdated" }, null]), { status: 400 });
+  assert.throws(() => plan({ mark: "stuck", comment: 1 }), { status: 400 });
+  assert.throws(() => plan([{ mark: "outdated" }, { mark: "outdated" }]), { status: 400, message: "Bad Request: duplicate marks" });
+});
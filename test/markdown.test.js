@@ -0,0 +1,40 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { toSafeMd, getMentionLink, getLogDirLinkMd } = require("../src/_markdown");
+
+const BASE_LOG_DIR = "https://logs.example.org/{pkgname}/";
+
+test("text outside links escapes every MarkdownV2 special character", () => {
+  assert.strictEqual(toSafeMd("a_b*c[d](e)~`>#+-=|{}.!\\"), "a\\_b\\*c\\[d\\]\\(e\\)\\~\\`\\>\\#\\+\\-\\=\\|\\{\\}\\.\\!\\\\");
+  assert.strictEqual(toSafeMd("&<"), "&<");
+});
+
+test("log links are plain text without PLCT_BASE_LOG_DIR", () => {
+  assert.strictEqual(getLogDirLinkMd("", "foo", "is failing."), "is failing\\.");
+});
+
+test("pkgnames are percent-encoded in log links, and ) is escaped", () => {
+  assert.strictEqual(getLogDirLinkMd(BASE_LOG_DIR, "a)b", "failing"), "[failing](https://logs.example.org/a\\)b/)");
+  assert.strictEqual(getLogDirLinkMd(BASE_LOG_DIR, "a\\b", "failing"), "[failing](https://logs.example.org/a%5Cb/)");
+  assert.strictEqual(getLogDirLinkMd(BASE_LOG_DIR, "a&b<c", "failing"), "[failing](https://logs.example.org/a%26b%3Cc/)");
+});
+
+test(") and \\ of PLCT_BASE_LOG_DIR itself are escaped in log links", () => {
+  assert.strictEqual(getLogDirLinkMd("https://logs.example.org/(riscv)\\{pkgname}", "foo", "failing"),
+    "[failing](https://logs.example.org/(riscv\\)\\\\foo)");
+});
+
+test("link texts are escaped as text", () => {
+  assert.strictEqual(getLogDirLinkMd(BASE_LOG_DIR, "foo", "a) \\ & <"), "[a\\) \\\\ & <](https://logs.example.org/foo/)");
+});
+
+test("aliases are escaped in mention links", () => {
+  assert.strictEqual(getMentionLink(1, undefined, "a)b", "c\\d"), "[a\\)b c\\\\d](tg://user?id=1)");
+  assert.strictEqual(getMentionLink(1, undefined, "a&b", "<c>"), "[a&b <c\\>](tg://user?id=1)");
+  assert.strictEqual(getMentionLink(1, "foo_bar"), "[@foo\\_bar](tg://user?id=1)");
+  assert.strictEqual(getMentionLink(1, undefined, "alice"), "[alice](tg://user?id=1)");
+  assert.strictEqual(getMentionLink(1, "alice", "", "", true), "[@alice\u200B](tg://user?id=1)");
+});
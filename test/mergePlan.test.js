@@ -0,0 +1,70 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { getMessages } = require("../src/_messages");
+const { planAutoMerge, getDeleteReply } = require("../src/_mergePlan");
+
+const by = (/** @type {number} */ uid, /** @type {string} */ alias) => ({ url: `[${alias}](tg://user?id=${uid})`, uid, alias });
+
+function state() {
+  return {
+    packageStatus: [
+      { userid: 1, username: undefined, packages: [{ name: "foo", lastActive: 0, assignedAt: 0 }] },
+      { userid: 2, username: undefined, packages: [{ name: "bar", lastActive: 0, assignedAt: 0 }] },
+    ],
+    packageMarks: [
+      { name: "foo", marks: [{ name: "failing", by: null, comment: "", markedAt: 0 }, { name: "stuck", by: by(1, "alice"), comment: "upstream", markedAt: 0 }] },
+      { name: "qux", marks: [{ name: "outdated_dep", by: by(2, "bob"), comment: "[foo]", markedAt: 0 }] },
+      { name: "quux", marks: [{ name: "missing_dep", by: by(2, "bob"), comment: "[bar] [FOO]", markedAt: 0 }] },
+    ],
+    clearOnMergeMarks: ["failing", "ready"],
+    refMarks: ["outdated_dep", "missing_dep"],
+    getAlias: (/** @type {number} */ uid) => ({ 1: "alice", 2: "bob" })[uid] || `uid=${uid}`,
+    messages: getMessages("en"),
+  };
+}
+
+test("a dry run changes no assignments or marks", () => {
+  const current = state();
+  const before = JSON.stringify(current);
+  planAutoMerge("foo", "ftbfs", current);
+  assert.strictEqual(JSON.stringify(current), before);
+});
+
+test("a dry run plans the unmarks, updates and messages of the deletion", () => {
+  const plan = planAutoMerge("foo", "ftbfs", state());
+  assert.deepStrictEqual(plan.packager, { tgUid: 1, alias: "alice" });
+  assert.deepStrictEqual(plan.unmarks, [{ pkgname: "foo", mark: "failing" }, { pkgname: "qux", mark: "outdated_dep" }]);
+  // only the reference to foo is dropped, in any case
+  assert.deepStrictEqual(plan.updates, [{ pkgname: "quux", mark: "missing_dep", comment: "[bar]" }]);
+  const messages = getMessages("en");
+  assert.deepStrictEqual(plan.messages, [
+    messages.mergedPing({ link: "[alice](tg://user?id=1)", pkgname: "foo", status: "ftbfs" }),
+    messages.mergedUnmark({ pkgname: "foo", status: "ftbfs", mark: "failing" }),
+    messages.cc({ links: ["[bob](tg://user?id=2)"] }),
+    messages.mergedRefUnmark({ pkgname: "qux", refPkgname: "foo", status: "ftbfs", mark: "outdated_dep" }),
+    messages.cc({ links: ["[bob](tg://user?id=2)"] }),
+    messages.mergedRefRemoved({ pkgname: "quux", refPkgname: "foo", mark: "missing_dep" }),
+  ]);
+});
+
+test("a package nobody holds or points to plans nothing", () => {
+  assert.deepStrictEqual(planAutoMerge("corge", "leaf", state()), { packager: null, unmarks: [], updates: [], messages: [] });
+});
+
+test("deleting an assigned package succeeds either way", () => {
+  assert.strictEqual(getDeleteReply({ userId: 1 }, false), "success");
+  assert.strictEqual(getDeleteReply({ userId: 1 }, true), "success");
+});
+
+test("without strict, packages nobody held are answered with 200", () => {
+  assert.strictEqual(getDeleteReply({ userId: null }, false), "package not found;success");
+  assert.strictEqual(getDeleteReply(null, false), "already deleted");
+});
+
+test("with strict, packages nobody held are answered with 404", () => {
+  assert.strictEqual(getDeleteReply({ userId: null }, true), null);
+  assert.strictEqual(getDeleteReply(null, true), null);
+});
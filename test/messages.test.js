@@ -0,0 +1,79 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { LANGUAGES, getMessages } = require("../src/_messages");
+
+// every argument any template takes; extra ones are ignored. The pkgname needs escaping in MarkdownV2
+const ARGS = {
+  link: "[alice](tg://user?id=1)",
+  links: ["[alice](tg://user?id=1)", "[bob](tg://user?id=2)"],
+  ownerLink: "[alice](tg://user?id=1)",
+  requesterLink: "[bob](tg://user?id=2)",
+  oldLink: "[alice](tg://user?id=1)",
+  newLink: "[bob](tg://user?id=2)",
+  oldAlias: "alice",
+  newAlias: "bob",
+  pkgname: "python-foo.bar",
+  pkgnames: ["foo", "bar"],
+  refPkgname: "qux",
+  status: "ftbfs",
+  groups: [{ pkgnames: ["foo"], status: "ftbfs" }, { pkgnames: ["bar"], status: "leaf" }],
+  mark: "outdated_dep",
+  marks: [{ mark: "failing", comment: "" }, { mark: "stuck", comment: "upstream" }],
+  logLink: "[failing](https://example.org/logs/foo)",
+  reason: "timeout",
+  requestId: "abc",
+  days: 30,
+  comment: "upstream",
+  from: "2024-01-01",
+  to: "2024-01-08",
+  closed: [{ alias: "alice", count: 2 }, { alias: null, count: 1 }],
+  newlyFailing: ["foo"],
+  oldest: [{ pkgname: "bar", alias: "alice", days: 40 }],
+};
+// templates taking an argument of the same name in another shape
+/**
+ * @type {Record<string, Record<string, any>>}
+ */
+const ARGS_OF = {
+  cascadeMark: { marks: ["failing", "stuck"] },
+  cascadeUnmark: { marks: ["failing", "stuck"] },
+};
+
+/**
+ * @param {any} bundle
+ */
+function describeKeys(bundle) {
+  return Object.keys(bundle).map(key => typeof bundle[key] === "function" ? key : `${key}: ${Object.keys(bundle[key]).join(" ")}`);
+}
+
+test("every bundle has the same keys", () => {
+  const [first, ...others] = LANGUAGES;
+  for(const lang of others) {
+    assert.deepStrictEqual(describeKeys(getMessages(lang)).sort(), describeKeys(getMessages(first)).sort(), lang);
+  }
+});
+
+test("every template of every bundle renders", () => {
+  for(const lang of LANGUAGES) {
+    const messages = getMessages(lang);
+    for(const [key, value] of Object.entries(messages)) {
+      if(typeof value !== "function") {
+        for(const [name, text] of Object.entries(value)) {
+          assert.ok(typeof text === "string" && text.length > 0, `${lang}.${key}.${name}`);
+        }
+        continue;
+      }
+      const text = /** @type {(args: any) => string} */ (value)({ ...ARGS, ...ARGS_OF[key] });
+      assert.strictEqual(typeof text, "string", `${lang}.${key}`);
+      assert.ok(text.length > 0, `${lang}.${key}`);
+      assert.doesNotMatch(text, /undefined|NaN|\[object /, `${lang}.${key}`);
+      if(key !== "autoDropReason") {
+        // MarkdownV2, so the plain arguments are escaped
+        assert.doesNotMatch(text, /python-foo\.bar/, `${lang}.${key}`);
+      }
+    }
+  }
+});
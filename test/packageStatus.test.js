@@ -0,0 +1,57 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { sortPackageStatus } = require("../src/_packageStatus");
+
+// 2024-01-10 12:00 in UTC+8
+const NOW = Date.UTC(2024, 0, 10, 4);
+const DAY = 86400e3;
+
+const status = [
+  { userid: 1, username: undefined, packages: [
+    { name: "foo", lastActive: 0, assignedAt: NOW - 3 * DAY },
+    { name: "bar", lastActive: 0, assignedAt: NOW - 10 * DAY },
+  ] },
+  { userid: 2, username: undefined, packages: [] },
+  { userid: 3, username: undefined, packages: [{ name: "baz", lastActive: 0, assignedAt: NOW - 5 * DAY }] },
+];
+
+const getAlias = (/** @type {number} */ uid) => ({ 1: "alice", 2: "bob", 3: "carol" })[uid] || `uid=${uid}`;
+
+test("the oldest packages and their packagers come first, packagers without packages last", () => {
+  const workList = sortPackageStatus(status, "oldest", getAlias, NOW);
+  assert.deepStrictEqual(workList.map(({ alias, packages }) => ({ alias, packages })), [
+    { alias: "alice", packages: ["bar", "foo"] },
+    { alias: "carol", packages: ["baz"] },
+    { alias: "bob", packages: [] },
+  ]);
+});
+
+test("newest reverses the order", () => {
+  const workList = sortPackageStatus(status, "newest", getAlias, NOW);
+  assert.deepStrictEqual(workList.map(({ alias, packages }) => ({ alias, packages })), [
+    { alias: "alice", packages: ["foo", "bar"] },
+    { alias: "carol", packages: ["baz"] },
+    { alias: "bob", packages: [] },
+  ]);
+});
+
+test("the status itself isn't reordered", () => {
+  sortPackageStatus(status, "oldest", getAlias, NOW);
+  assert.deepStrictEqual(status[0].packages.map(pkg => pkg.name), ["foo", "bar"]);
+});
+
+test("daysAssigned counts calendar days in UTC+8", () => {
+  const [alice] = sortPackageStatus(status, "oldest", getAlias, NOW);
+  assert.deepStrictEqual(alice.daysAssigned, { bar: 10, foo: 3 });
+  assert.deepStrictEqual(alice.assignedAt, { bar: new Date(NOW - 10 * DAY).toISOString(), foo: new Date(NOW - 3 * DAY).toISOString() });
+  // 23:30 the day before is a day ago at 00:30, 00:30 of today is not
+  const midnight = Date.UTC(2024, 0, 9, 16);
+  const late = [{ userid: 1, username: undefined, packages: [
+    { name: "yesterday", lastActive: 0, assignedAt: midnight - 0.5 * 3600e3 },
+    { name: "today", lastActive: 0, assignedAt: midnight + 0.25 * 3600e3 },
+  ] }];
+  assert.deepStrictEqual(sortPackageStatus(late, "oldest", getAlias, midnight + 0.5 * 3600e3)[0].daysAssigned, { yesterday: 1, today: 0 });
+});
@@ -0,0 +1,42 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { PKGNAME_MAX_LENGTH, isValidPkgname, decodePkgnameSegment } = require("../src/_pkgname");
+
+test("Arch pkgnames are accepted", () => {
+  for(const pkgname of ["foo", "python-foo", "lib32-gcc-libs", "gtk+", "libc++", "foo_bar", "@foo", "0ad", "foo.bar"]) {
+    assert.strictEqual(isValidPkgname(pkgname), true, pkgname);
+  }
+});
+
+test("pkgnames are at most 128 chars", () => {
+  assert.strictEqual(PKGNAME_MAX_LENGTH, 128);
+  assert.strictEqual(isValidPkgname("a".repeat(128)), true);
+  assert.strictEqual(isValidPkgname("a".repeat(129)), false);
+});
+
+test("pkgnames don't start with . or -", () => {
+  assert.strictEqual(isValidPkgname(".foo"), false);
+  assert.strictEqual(isValidPkgname("-foo"), false);
+  assert.strictEqual(isValidPkgname(".."), false);
+  assert.strictEqual(isValidPkgname(""), false);
+});
+
+test("uppercase pkgnames are refused", () => {
+  assert.strictEqual(isValidPkgname("Foo"), false);
+  assert.strictEqual(isValidPkgname("fooBAR"), false);
+});
+
+test("pkgnames with / are refused", () => {
+  assert.strictEqual(isValidPkgname("foo/bar"), false);
+  assert.strictEqual(isValidPkgname("../foo"), false);
+});
+
+test("path segments are checked after decoding", () => {
+  assert.strictEqual(decodePkgnameSegment("gtk%2B"), "gtk+");
+  assert.strictEqual(decodePkgnameSegment("foo%2Fbar"), null);
+  assert.strictEqual(decodePkgnameSegment("%2E%2E%2Ffoo"), null);
+  assert.strictEqual(decodePkgnameSegment("%E0%A4%A"), null);
+});
@@ -0,0 +1,47 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { parseQuietHours, isQuietHour } = require("../src/_quietHours");
+
+/**
+ * @param {number} hour in UTC+8
+ */
+function at(hour) {
+  return new Date(Date.UTC(2024, 0, 1, (hour - 8 + 24) % 24, 30));
+}
+
+/**
+ * @param {[number, number] | null} quietHours
+ */
+function quietHoursOf(quietHours) {
+  return Array.from({ length: 24 }, (_, hour) => hour).filter(hour => isQuietHour(at(hour), quietHours));
+}
+
+test("windows are parsed from start-end", () => {
+  assert.deepStrictEqual(parseQuietHours("22-8"), [22, 8]);
+  assert.deepStrictEqual(parseQuietHours("09-17"), [9, 17]);
+  assert.deepStrictEqual(parseQuietHours(" 0-23 "), [0, 23]);
+});
+
+test("blank or invalid windows turn quiet hours off", () => {
+  for(const hours of [undefined, "", "-", "22", "22-", "a-b", "22-24", "25-8", "22-8-9", "-1-8"]) {
+    assert.strictEqual(parseQuietHours(hours), null, String(hours));
+  }
+  assert.deepStrictEqual(quietHoursOf(null), []);
+});
+
+test("a window within a day covers its start, not its end", () => {
+  assert.deepStrictEqual(quietHoursOf([9, 12]), [9, 10, 11]);
+});
+
+test("a window wrapping past midnight covers both days", () => {
+  assert.deepStrictEqual(quietHoursOf([22, 3]), [0, 1, 2, 22, 23]);
+  assert.deepStrictEqual(quietHoursOf([23, 0]), [23]);
+  assert.deepStrictEqual(quietHoursOf([0, 1]), [0]);
+});
+
+test("a window ending where it starts is empty", () => {
+  assert.deepStrictEqual(quietHoursOf([8, 8]), []);
+});
@@ -0,0 +1,119 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { getRetryAfter, isTransientSendError, retrySend } = require("../src/_sendRetry");
+
+/**
+ * @param {number} errorCode
+ * @param {number} [retryAfter] seconds
+ */
+function telegramError(errorCode, retryAfter) {
+  const body = { ok: false, error_code: errorCode, description: "", parameters: retryAfter === undefined ? undefined : { retry_after: retryAfter } };
+  return Object.assign(new Error(`ETELEGRAM: ${errorCode}`), { code: "ETELEGRAM", response: { body } });
+}
+
+/**
+ * @param {any[]} results errors to throw or values to resolve with, one per call
+ */
+function sender(results) {
+  const send = async () => {
+    const result = results[send.calls++];
+    if(result instanceof Error) throw result;
+    return result;
+  };
+  send.calls = 0;
+  return send;
+}
+
+/**
+ * @param {Partial<import("../src/_sendRetry").RetrySendOptions<any>>} [overrides]
+ */
+function options(overrides = {}) {
+  /** @type {number[]} */
+  const delays = [];
+  /** @type {number[]} */
+  const requeued = [];
+  return {
+    delays,
+    requeued,
+    options: {
+      maxRetries: 3,
+      retryDelay: 1000,
+      maxRetryAfter: 30e3,
+      /** @param {number} retryAfter */
+      requeue: async retryAfter => { requeued.push(retryAfter); return "requeued"; },
+      /** @param {number} ms */
+      delayFn: async ms => { delays.push(ms); },
+      random: () => 0,
+      ...overrides,
+    },
+  };
+}
+
+test("transient errors are told from the others", () => {
+  assert.strictEqual(isTransientSendError(telegramError(500)), true);
+  assert.strictEqual(isTransientSendError(telegramError(429, 3)), true);
+  assert.strictEqual(isTransientSendError(Object.assign(new Error("EFATAL"), { code: "EFATAL" })), true);
+  assert.strictEqual(isTransientSendError(telegramError(400)), false);
+  assert.strictEqual(isTransientSendError(telegramError(403)), false);
+  assert.strictEqual(isTransientSendError(new Error("?")), false);
+});
+
+test("retry_after is read in ms", () => {
+  assert.strictEqual(getRetryAfter(telegramError(429, 3)), 3000);
+  assert.strictEqual(getRetryAfter(telegramError(500)), null);
+  assert.strictEqual(getRetryAfter(undefined), null);
+});
+
+test("a send failing twice then succeeding is retried with doubling delays", async () => {
+  const { delays, options: opts } = options();
+  const send = sender([telegramError(502), "sent"]);
+  assert.strictEqual(await retrySend(send, telegramError(500), opts), "sent");
+  assert.strictEqual(send.calls, 2);
+  assert.deepStrictEqual(delays, [1000, 2000]);
+});
+
+test("the jitter adds up to half of the backoff", async () => {
+  const { delays, options: opts } = options({ random: () => 0.999 });
+  await retrySend(sender([telegramError(500), "sent"]), telegramError(500), opts);
+  assert.deepStrictEqual(delays, [1500, 2999]);
+});
+
+test("a 429 is retried after its retry_after", async () => {
+  const { delays, requeued, options: opts } = options();
+  const send = sender(["sent"]);
+  assert.strictEqual(await retrySend(send, telegramError(429, 5), opts), "sent");
+  assert.deepStrictEqual(delays, [5000]);
+  assert.deepStrictEqual(requeued, []);
+});
+
+test("a 429 with a retry_after longer than maxRetryAfter is requeued", async () => {
+  const { delays, requeued, options: opts } = options();
+  const send = sender([]);
+  assert.strictEqual(await retrySend(send, telegramError(429, 31), opts), "requeued");
+  assert.strictEqual(send.calls, 0);
+  assert.deepStrictEqual(delays, []);
+  assert.deepStrictEqual(requeued, [31e3]);
+});
+
+test("after a non-transient error only the first retry is made", async () => {
+  /** @type {any[]} */
+  const errors = [];
+  let gaveUp = 0;
+  const { options: opts } = options({ onError: err => errors.push(err), onGiveUp: () => gaveUp++ });
+  const send = sender([telegramError(400)]);
+  await assert.rejects(retrySend(send, telegramError(400), opts), { message: "ETELEGRAM: 400 (gave up after 2 attempts)" });
+  assert.strictEqual(send.calls, 1);
+  assert.strictEqual(errors.length, 1);
+  assert.strictEqual(gaveUp, 1);
+});
+
+test("it gives up after maxRetries", async () => {
+  const { delays, options: opts } = options();
+  const send = sender([telegramError(500), telegramError(500), telegramError(503)]);
+  await assert.rejects(retrySend(send, telegramError(500), opts), { message: "ETELEGRAM: 503 (gave up after 4 attempts)" });
+  assert.strictEqual(send.calls, 3);
+  assert.deepStrictEqual(delays, [1000, 2000, 4000]);
+});
@@ -0,0 +1,55 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { SlidingWindowLimiter } = require("../src/_slidingWindow");
+
+/**
+ * @param {number} limit
+ * @param {number} window
+ */
+function limiterAt(limit, window) {
+  const clock = { now: 1000e3 };
+  return { clock, limiter: new SlidingWindowLimiter(limit, window, () => clock.now) };
+}
+
+test("the call after the limit within the window is refused until the oldest one leaves it", () => {
+  const { clock, limiter } = limiterAt(3, 60e3);
+  for(let i = 0; i < 3; i++) {
+    assert.strictEqual(limiter.take("ci"), null);
+    clock.now += 10e3;
+  }
+  // 30s after the first call, which leaves the window 30s later
+  assert.strictEqual(limiter.take("ci"), 30);
+  clock.now += 29.5e3;
+  assert.strictEqual(limiter.take("ci"), 1);
+  clock.now += 0.5e3;
+  assert.strictEqual(limiter.take("ci"), null);
+});
+
+test("refused calls aren't counted", () => {
+  const { clock, limiter } = limiterAt(1, 60e3);
+  assert.strictEqual(limiter.take("ci"), null);
+  clock.now += 30e3;
+  assert.strictEqual(limiter.take("ci"), 30);
+  clock.now += 30e3;
+  assert.strictEqual(limiter.take("ci"), null);
+});
+
+test("every key has its own window", () => {
+  const { limiter } = limiterAt(1, 60e3);
+  assert.strictEqual(limiter.take("ci"), null);
+  assert.strictEqual(limiter.take("admin"), null);
+  assert.strictEqual(limiter.take("ci"), 60);
+});
+
+test("keys without calls in the window are pruned", () => {
+  const { clock, limiter } = limiterAt(2, 60e3);
+  limiter.take("ci");
+  clock.now += 30e3;
+  limiter.take("admin");
+  clock.now += 30e3;
+  limiter.prune();
+  assert.deepStrictEqual([...limiter.calls.keys()], ["admin"]);
+});
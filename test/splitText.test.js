@@ -0,0 +1,81 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { splitLongText } = require("../src/_splitText");
+
+// what sendMessage() and POST /notify split at, below Telegram's 4096
+const LIMIT = 4000;
+
+test("a text within the limit is kept whole", () => {
+  const text = "rebuild starting, please pause merges\nsecond line";
+  assert.deepStrictEqual(splitLongText(text, LIMIT), [text]);
+});
+
+test("an over-length text is split at line breaks, keeping every line in order", () => {
+  const lines = Array.from({ length: 600 }, (_, i) => `line of text number ${i}`);
+  const text = lines.join("\n");
+  assert.ok(text.length > LIMIT * 3);
+  const parts = splitLongText(text, LIMIT);
+  assert.ok(parts.length > 3);
+  parts.forEach(part => assert.ok(part.length <= LIMIT, `part of ${part.length} characters`));
+  assert.deepStrictEqual(parts.join("\n").split("\n"), lines);
+});
+
+test("an over-length line is cut at spaces", () => {
+  const words = Array.from({ length: 2000 }, (_, i) => `word${i}`);
+  const parts = splitLongText(words.join(" "), LIMIT);
+  assert.ok(parts.length > 1);
+  parts.forEach(part => assert.ok(part.length <= LIMIT));
+  assert.deepStrictEqual(parts.join(" ").split(" "), words);
+});
+
+test("links and escapes are never cut in half", () => {
+  const link = "[alice](tg://user?id=1)";
+  const text = ("x".repeat(3980) + link + "\\.").repeat(2);
+  const parts = splitLongText(text, LIMIT);
+  parts.forEach(part => {
+    assert.ok(part.length <= LIMIT);
+    assert.ok(!part.endsWith("\\"), "a part ends with a dangling escape");
+    assert.strictEqual(part.split("[").length, part.split(")").length, "a part has half a link");
+  });
+  assert.strictEqual(parts.join(""), text);
+});
+
+test("code blocks are closed and reopened across parts", () => {
+  const text = "```\n" + Array.from({ length: 500 }, (_, i) => `code line ${i}`).join("\n") + "\n```";
+  const parts = splitLongText(text, LIMIT);
+  assert.ok(parts.length > 1);
+  parts.forEach(part => {
+    assert.ok(part.length <= LIMIT);
+    assert.strictEqual(part.split("```").length % 2, 1, "a part leaves a code block open");
+  });
+});
+
+test("a 10 KB package list with mention links is split without cutting a link", () => {
+  const lines = Array.from({ length: 250 }, (_, i) =>
+    `\\(auto\\-unmark\\) [packager${i % 7}](tg://user?id=${1000 + i % 7}) pkg\\-${i}\\-name no longer marked`);
+  const text = lines.join("\n");
+  assert.ok(text.length > 10 * 1024);
+  const parts = splitLongText(text, LIMIT);
+  assert.ok(parts.length >= 3);
+  parts.forEach(part => {
+    assert.ok(part.length <= LIMIT);
+    assert.ok(!part.endsWith("\\"), "a part ends with a dangling escape");
+    assert.strictEqual(part.match(/\[/g).length, part.match(/\(tg:\/\/user\?id=\d+\)/g).length, "a part has half a mention link");
+  });
+  assert.deepStrictEqual(parts.join("\n").split("\n"), lines);
+});
+
+test("a 10 KB package list on a single line is cut between links", () => {
+  const links = Array.from({ length: 350 }, (_, i) => `[packager${i}](tg://user?id=${1000 + i})`);
+  const text = `ping ${links.join(" ")}`;
+  assert.ok(text.length > 10 * 1024);
+  const parts = splitLongText(text, LIMIT);
+  assert.strictEqual(parts.join(" "), text);
+  parts.forEach(part => {
+    assert.ok(part.length <= LIMIT);
+    assert.match(part, /^(ping )?(\[packager\d+\]\(tg:\/\/user\?id=\d+\)( |$))+$/);
+  });
+});
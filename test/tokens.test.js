@@ -0,0 +1,66 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+const crypto = require("crypto");
+
+const { verifyToken, verifySignature } = require("../src/_tokens");
+
+test("the expected token is accepted", () => {
+  assert.strictEqual(verifyToken("secret", "secret"), true);
+});
+
+test("tokens of another length are refused", () => {
+  assert.strictEqual(verifyToken("secre", "secret"), false);
+  assert.strictEqual(verifyToken("secret!", "secret"), false);
+  assert.strictEqual(verifyToken("", "secret"), false);
+});
+
+test("tokens of the same length but other content are refused", () => {
+  assert.strictEqual(verifyToken("secreT", "secret"), false);
+  assert.strictEqual(verifyToken("terces", "secret"), false);
+});
+
+test("missing tokens are refused, as is everything when none is configured", () => {
+  assert.strictEqual(verifyToken(null, "secret"), false);
+  assert.strictEqual(verifyToken(undefined, "secret"), false);
+  assert.strictEqual(verifyToken(["secret"], "secret"), false);
+  assert.strictEqual(verifyToken("", ""), false);
+  assert.strictEqual(verifyToken("secret", undefined), false);
+});
+
+const NOW = 1700000000e3;
+const TIMESTAMP = String(NOW / 1000);
+
+/**
+ * @param {string} path
+ * @param {string} timestamp
+ */
+function sign(path, timestamp, secret = "key") {
+  return crypto.createHmac("sha256", secret).update(path + timestamp, "utf8").digest("hex");
+}
+
+test("a signature of the path and timestamp is accepted, in either case", () => {
+  const signature = sign("/add/foo", TIMESTAMP);
+  assert.strictEqual(verifySignature(signature, TIMESTAMP, "/add/foo", "key", 300, NOW), true);
+  assert.strictEqual(verifySignature(signature.toUpperCase(), TIMESTAMP, "/add/foo", "key", 300, NOW), true);
+});
+
+test("signatures of another path, key or length are refused", () => {
+  const signature = sign("/add/foo", TIMESTAMP);
+  assert.strictEqual(verifySignature(signature, TIMESTAMP, "/add/bar", "key", 300, NOW), false);
+  assert.strictEqual(verifySignature(sign("/add/foo", TIMESTAMP, "other"), TIMESTAMP, "/add/foo", "key", 300, NOW), false);
+  assert.strictEqual(verifySignature(signature.slice(1), TIMESTAMP, "/add/foo", "key", 300, NOW), false);
+  assert.strictEqual(verifySignature(signature, TIMESTAMP, "/add/foo", undefined, 300, NOW), false);
+});
+
+test("timestamps out of maxAge or not a number are refused", () => {
+  const old = String(NOW / 1000 - 301);
+  assert.strictEqual(verifySignature(sign("/add/foo", old), old, "/add/foo", "key", 300, NOW), false);
+  const future = String(NOW / 1000 + 301);
+  assert.strictEqual(verifySignature(sign("/add/foo", future), future, "/add/foo", "key", 300, NOW), false);
+  const edge = String(NOW / 1000 - 300);
+  assert.strictEqual(verifySignature(sign("/add/foo", edge), edge, "/add/foo", "key", 300, NOW), true);
+  assert.strictEqual(verifySignature(sign("/add/foo", "1e9"), "1e9", "/add/foo", "key", 300, NOW), false);
+  assert.strictEqual(verifySignature(sign("/add/foo", TIMESTAMP), undefined, "/add/foo", "key", 300, NOW), false);
+});
@@ -0,0 +1,85 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { buildWeeklyReport } = require("../src/_weeklyReport");
+
+const DAY = 86400e3;
+const NOW = 100 * DAY;
+const SINCE = NOW - 7 * DAY;
+
+/**
+ * @param {string} pkgname
+ * @param {import("../src/utils").HistoryInterface["action"]} action
+ * @param {import("../src/utils").HistoryActorInterface["via"]} via
+ * @param {number | null} uid
+ * @param {number} time
+ * @param {string} [detail]
+ * @returns {import("../src/utils").HistoryInterface}
+ */
+function entry(pkgname, action, via, uid, time, detail = "") {
+  return { pkgname, action, actor: { via, uid }, detail, time };
+}
+
+const history = [
+  // before the week
+  entry("old", "add", "bot", 1, SINCE - DAY),
+  entry("old", "drop", "bot", 1, SINCE - DAY / 2),
+  entry("foo", "add", "bot", 1, SINCE - DAY),
+  entry("bar", "add", "bot", 1, SINCE - DAY),
+  entry("baz", "add", "bot", 2, SINCE - DAY),
+  entry("baz", "reassign", "api", null, SINCE - DAY / 2, "2 -> 3"),
+  entry("stuck", "add", "bot", 2, SINCE - DAY),
+  // within the week
+  entry("foo", "drop", "bot", 1, SINCE + DAY),
+  entry("bar", "drop", "api", null, SINCE + DAY),
+  entry("baz", "drop", "api", null, SINCE + 2 * DAY),
+  entry("stuck", "drop", "auto", null, SINCE + 2 * DAY, "held for 30 days"),
+  entry("unknown", "drop", "api", null, SINCE + 3 * DAY),
+  entry("qux", "mark", "api", null, SINCE + DAY, "failing"),
+  entry("quux", "mark", "api", null, SINCE + DAY, "failing"),
+  entry("quux", "unmark", "api", null, SINCE + 2 * DAY, "failing"),
+  entry("corge", "mark", "bot", 1, SINCE + DAY, "stuck waiting upstream"),
+];
+
+const status = [
+  { userid: 1, packages: [{ name: "new", lastActive: NOW, assignedAt: NOW - DAY }] },
+  { userid: 2, packages: [
+    { name: "ancient", lastActive: 0, assignedAt: 10 * DAY },
+    { name: "older", lastActive: 0, assignedAt: 20 * DAY },
+  ] },
+];
+
+const marks = [
+  { name: "qux", marks: [{ name: "failing" }] },
+  { name: "quux", marks: [] },
+  { name: "corge", marks: [{ name: "stuck" }] },
+];
+
+test("releases of the week are counted for whoever held the package", () => {
+  const { closed } = buildWeeklyReport(history, status, marks, SINCE, 10);
+  assert.deepStrictEqual(closed, [
+    // foo by /merge, bar by /delete, followed through its add
+    { userid: 1, count: 2 },
+    // baz was reassigned to 3 before /delete
+    { userid: 3, count: 1 },
+    // claimed before the history was kept
+    { userid: null, count: 1 },
+  ]);
+});
+
+test("only packages newly marked failing and still marked so are listed", () => {
+  assert.deepStrictEqual(buildWeeklyReport(history, status, marks, SINCE, 10).newlyFailing, ["qux"]);
+});
+
+test("the oldest assignments are listed oldest first, up to the limit", () => {
+  assert.deepStrictEqual(buildWeeklyReport(history, status, marks, SINCE, 2).oldest, [
+    { userid: 2, pkgname: "ancient", assignedAt: 10 * DAY },
+    { userid: 2, pkgname: "older", assignedAt: 20 * DAY },
+  ]);
+});
+
+test("an empty week reports nothing", () => {
+  assert.deepStrictEqual(buildWeeklyReport([], [], [], SINCE, 10), { closed: [], newlyFailing: [], oldest: [] });
+});
@@ -0,0 +1,50 @@
+//@ts-check
+
+const { test } = require("node:test");
+const assert = require("node:assert");
+
+const { FS_RETRY_ATTEMPTS, withRetry } = require("../src/_withRetry");
+
+/**
+ * @param {string} code
+ */
+function fsError(code) {
+  return Object.assign(new Error(code), { code });
+}
+
+/**
+ * @param {(attempt: number) => any} attempt called with 1, 2, ...
+ */
+function counted(attempt) {
+  const fn = async () => attempt(++fn.calls);
+  fn.calls = 0;
+  return fn;
+}
+
+test("a transient error is retried until the write succeeds", async () => {
+  /** @type {number[]} */
+  const delays = [];
+  const fn = counted(n => {
+    if(n === 1) throw fsError("EBUSY");
+    return "done";
+  });
+  assert.strictEqual(await withRetry(fn, "test", async ms => { delays.push(ms); }), "done");
+  assert.strictEqual(fn.calls, 2);
+  assert.deepStrictEqual(delays, [100]);
+});
+
+test("other errors fail at once", async () => {
+  const fn = counted(() => { throw fsError("ENOSPC"); });
+  await assert.rejects(withRetry(fn, "test", async () => {}), { code: "ENOSPC" });
+  assert.strictEqual(fn.calls, 1);
+});
+
+test("it gives up after FS_RETRY_ATTEMPTS with the last error", async () => {
+  /** @type {number[]} */
+  const delays = [];
+  const fn = counted(n => { throw fsError(n === FS_RETRY_ATTEMPTS ? "ESTALE" : "EAGAIN"); });
+  await assert.rejects(withRetry(fn, "test", async ms => { delays.push(ms); }), { code: "ESTALE" });
+  assert.strictEqual(FS_RETRY_ATTEMPTS, 5);
+  assert.strictEqual(fn.calls, 5);
+  assert.deepStrictEqual(delays, [100, 200, 400, 800]);
+});